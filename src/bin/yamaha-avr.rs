@@ -1,14 +1,26 @@
 #[macro_use]
 extern crate clap;
+extern crate chrono;
 extern crate yamaha_avr;
 
-use clap::App;
+use chrono::NaiveTime;
+use clap::{App, ArgMatches};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+use yamaha_avr::{Error, Result};
+use yamaha_avr::yamaha::{Band, CancellationToken, Frequency, InputId, QuietHours, SleepTimer, Volume, VolumeLimits};
 
 fn main() {
     let matches = clap_app!(@app(App::new("Yamaha AVR Remote"))
         (version: "0.2.0")
         (author: "Max Jöhnk <maxjoehnk@gmail.com>")
-        (@arg ip: --ip +takes_value "Set the AVR Ip")
+        (@arg ip: --ip +takes_value "Set the AVR Ip, discovers the AVR when omitted")
+        (@arg zone: --zone +takes_value "Set the Zone to control (main, zone2, zone3, zone4), defaults to main")
+        (@arg config: --config +takes_value "Read the Config from this File, defaults to ~/.config/yamaha-avr/config")
         (@subcommand power =>
             (about: "Get/Set Power")
             (@arg value: "Get/Set the Power state")
@@ -17,33 +29,154 @@ fn main() {
             (about: "Mute/Unmute")
             (@arg value: "Set the Mute State")
         )
+        (@subcommand status =>
+            (about: "Show the complete Status of the Zone")
+        )
+        (@subcommand info =>
+            (about: "Show Model, System ID, Firmware and Features of the Receiver")
+        )
         (@subcommand inputs =>
             (about: "Get available Inputs")
+            (@subcommand rename =>
+                (about: "Rename an Input")
+                (@arg input: +required "The Input to rename, e.g. HDMI2")
+                (@arg title: +required "The new Title, up to 9 Characters")
+            )
         )
         (@subcommand select =>
             (about: "Select Input")
-            (@arg input: +required "The Input to select")
+            (@arg input: +required "The Input to select, either its Name (e.g. \"Turntable\") or HDMI1, AV1, ...")
+        )
+        (@subcommand capabilities =>
+            (about: "List the Commands described by the Receiver")
+        )
+        (@subcommand direct =>
+            (about: "Get/Set Pure Direct")
+            (@arg value: "Set the Pure Direct State")
+        )
+        (@subcommand drc =>
+            (about: "Get/Set Adaptive DRC")
+            (@arg value: "Set the Adaptive DRC State")
+        )
+        (@subcommand hdmi =>
+            (about: "Get/Set the HDMI Outputs and HDMI Standby Through")
+            (@arg output: "The Number of the Output, lists all Outputs when omitted")
+            (@arg value: "Set the Output State")
+            (@arg standby_through: --("standby-through") +takes_value "Set HDMI Standby Through (on/off)")
+        )
+        (@subcommand program =>
+            (about: "Get/Set the Sound Program")
+            (@arg value: "The Sound Program to select, e.g. \"Hall in Munich\"")
+            (@arg list: --list "List the documented Sound Programs")
+            (@arg straight: --straight +takes_value "Set Straight (on/off)")
+            (@arg enhancer: --enhancer +takes_value "Set the Enhancer (on/off)")
+            (@arg cinema_dsp: --("3d-cinema-dsp") +takes_value "Set CINEMA DSP 3D (on/off)")
+        )
+        (@subcommand scene =>
+            (about: "List or recall Scenes")
+            (@arg scene: "The Number or Name of the Scene to recall, lists the Scenes when omitted")
+        )
+        (@subcommand sleep =>
+            (about: "Get/Set the Sleep Timer")
+            (@arg value: "Minutes until Standby (30, 60, 90, 120) or off")
+        )
+        (@subcommand tone =>
+            (@setting AllowNegativeNumbers)
+            (about: "Get/Set Bass and Treble")
+            (@arg bass: --bass +takes_value +allow_hyphen_values "Set the Bass in dB (-6 to 6)")
+            (@arg treble: --treble +takes_value +allow_hyphen_values "Set the Treble in dB (-6 to 6)")
+        )
+        (@subcommand tuner =>
+            (about: "Control the FM/AM/DAB Tuner, shows the Tuner Status without Subcommand")
+            (@subcommand band =>
+                (about: "Get/Set the Band")
+                (@arg value: "FM, AM or DAB")
+            )
+            (@subcommand frequency =>
+                (about: "Get/Set the Frequency")
+                (@arg value: "The Frequency to tune to, e.g. 101.1 (MHz) or 1080 (kHz)")
+            )
+            (@subcommand seek =>
+                (about: "Search the next Station")
+                (@arg direction: +required "up or down")
+            )
+            (@subcommand presets =>
+                (about: "List the stored Presets")
+                (@arg dab: --dab "List the DAB Presets")
+            )
+            (@subcommand preset =>
+                (about: "Recall a Preset")
+                (@arg number: +required "The Number of the Preset, starting at 1")
+                (@arg dab: --dab "Recall a DAB Preset")
+            )
+            (@subcommand store =>
+                (about: "Store the current Frequency or DAB Service as Preset")
+                (@arg number: +required "The Number of the Preset, starting at 1")
+                (@arg dab: --dab "Store a DAB Preset")
+            )
+            (@subcommand services =>
+                (about: "List the DAB Services")
+            )
+            (@subcommand service =>
+                (about: "Select a DAB Service")
+                (@arg service: +required "The Number or Label of the Service")
+            )
         )
         (@subcommand volume =>
             (@setting AllowNegativeNumbers)
             (about: "Get/set the volume")
-            (@arg value: "The Volume to set to")
+            (@arg value: "Absolute Volume in dB when unsigned, e.g. 0, a relative Change when signed, e.g. +2 or -2; use `volume set -35.5` for negative absolute Volumes")
+            (@subcommand set =>
+                (@setting AllowNegativeNumbers)
+                (about: "Set the absolute Volume")
+                (@arg value: +required "The Volume in dB to set to, e.g. -35.5")
+            )
+            (@subcommand fade =>
+                (@setting AllowNegativeNumbers)
+                (about: "Gradually change the Volume")
+                (@arg target: +required "The Volume in dB to fade to, e.g. -50")
+                (@arg over: --over +takes_value +required "Duration of the Fade, e.g. 10s, 2m or 500ms")
+            )
         )
     ).get_matches();
-    let ip: String = matches.value_of("ip").unwrap_or("192.168.2.102").to_owned();
-    let mut avr = yamaha_avr::connect(ip);
+    if let Err(err) = run(&matches) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<()> {
+    let config = load_config(matches.value_of("config"))?;
+    let mut avr = match matches.value_of("ip").or_else(|| config.ip.as_ref().map(String::as_ref)) {
+        Some(ip) => yamaha_avr::connect(ip.to_owned())?,
+        None => {
+            let mut receivers = yamaha_avr::discover()?;
+            if receivers.is_empty() {
+                println!("No AVR found, use --ip to connect manually");
+                return Ok(());
+            }
+            receivers.remove(0)
+        }
+    };
+    avr.set_volume_limits(config.volume_limits);
+    if matches.subcommand_matches("program").is_some_and(|matches| matches.is_present("list")) {
+        // Models without a Unit Description list the documented Programs instead
+        avr.load_capabilities().ok();
+    }
+    let zone = match matches.value_of("zone") {
+        Some(zone) => avr.zone(zone.parse()?)?,
+        None => avr.main_zone()
+    };
     if let Some(matches) = matches.subcommand_matches("power") {
         match matches.value_of("value") {
             Some(value) => {
-                let parsed_value = parse_bool_state(value);
-                if parsed_value.is_some() {
-                    avr.set_power(parsed_value.unwrap()).unwrap();
-                }else {
-                    println!("Invalid value {}", value);
+                match parse_bool_state(value) {
+                    Some(value) => zone.set_power(value)?,
+                    None => println!("Invalid value {}", value)
                 }
             },
             None => {
-                let power = avr.get_power().unwrap();
+                let power = zone.get_power()?;
                 println!("Power: {}", transform_bool_state(power));
             }
         }
@@ -51,45 +184,524 @@ fn main() {
     if let Some(matches) = matches.subcommand_matches("mute") {
         match matches.value_of("value") {
             Some(value) => {
-                let parsed_value = parse_bool_state(value);
-                if parsed_value.is_some() {
-                    avr.set_mute(parsed_value.unwrap()).unwrap();
-                }else {
-                    println!("Invalid value {}", value);
+                match parse_bool_state(value) {
+                    Some(value) => zone.set_mute(value)?,
+                    None => println!("Invalid value {}", value)
                 }
             },
             None => {
-                let muted = avr.get_mute().unwrap();
+                let muted = zone.get_mute()?;
                 println!("Mute: {}", transform_bool_state(muted));
             }
         }
     }
-    if matches.subcommand_matches("inputs").is_some() {
-        let inputs = avr.get_inputs().unwrap();
-        for input in inputs {
-            println!("{}", input.name);
+    if matches.subcommand_matches("status").is_some() {
+        let info = zone.get_basic_info()?;
+        println!("Power: {}", transform_bool_state(info.power));
+        if let Some(sleep) = info.sleep {
+            println!("Sleep: {}", sleep);
+        }
+        if let Some(volume) = info.volume {
+            println!("Volume: {}", volume);
+        }
+        println!("Mute: {}", transform_bool_state(info.mute));
+        match (&info.input, info.input_info.as_ref().and_then(|input| input.title.as_ref())) {
+            (Some(input), Some(title)) => println!("Input: {} ({})", input, title),
+            (Some(input), None) => println!("Input: {}", input),
+            (None, _) => {}
+        }
+        if let Some(surround) = info.surround {
+            if let Some(program) = surround.sound_program {
+                println!("Sound Program: {}", program);
+            }
+            println!("Straight: {}", transform_bool_state(surround.straight));
+            println!("Enhancer: {}", transform_bool_state(surround.enhancer));
+            println!("3D Cinema DSP: {}", transform_bool_state(surround.cinema_dsp_3d));
+        }
+        if let Some(sound_video) = info.sound_video {
+            if let Some(tone) = sound_video.tone {
+                println!("Bass: {} dB", tone.bass);
+                println!("Treble: {} dB", tone.treble);
+            }
+            println!("Pure Direct: {}", transform_bool_state(sound_video.direct));
+            println!("Adaptive DRC: {}", transform_bool_state(sound_video.adaptive_drc));
+            if let Some(standby_through) = sound_video.hdmi_standby_through {
+                println!("HDMI Standby Through: {}", transform_bool_state(standby_through));
+            }
+            for output in sound_video.hdmi_outputs {
+                println!("HDMI Out {}: {}", output.number, transform_bool_state(output.enabled));
+            }
+        }
+    }
+    if matches.subcommand_matches("info").is_some() {
+        let config = avr.get_system_config()?;
+        if let Some(device_info) = avr.device_info() {
+            println!("Name: {}", device_info.friendly_name);
+        }
+        if let Some(model_name) = config.model_name {
+            println!("Model: {}", model_name);
+        }
+        if let Some(system_id) = config.system_id {
+            println!("System ID: {}", system_id);
+        }
+        if let Some(firmware_version) = config.firmware_version {
+            println!("Firmware: {}", firmware_version);
+        }
+        println!("IP: {}", avr.ip());
+        println!("Zones: {}", config.available_zones.join(", "));
+        let mut features: Vec<&str> = config.features.available().into_iter().map(|feature| feature.tag()).collect();
+        features.sort_unstable();
+        println!("Features: {}", features.join(", "));
+    }
+    if let Some(matches) = matches.subcommand_matches("inputs") {
+        if let Some(matches) = matches.subcommand_matches("rename") {
+            let input: InputId = matches.value_of("input").unwrap().parse()?;
+            avr.rename_input(&input, matches.value_of("title").unwrap())?;
+        }else {
+            let inputs = avr.get_inputs()?;
+            for input in inputs {
+                println!("{}", input);
+            }
         }
     }
     if let Some(matches) = matches.subcommand_matches("select") {
-        let input = matches.value_of("input").unwrap().to_owned();
-        avr.select_input(input, None).unwrap();
+        let input = matches.value_of("input").unwrap();
+        match zone.select_input_by_name(input) {
+            Ok(_) => {},
+            Err(Error::AmbiguousInput(candidates)) => {
+                println!("{} matches multiple Inputs:", input);
+                for candidate in candidates {
+                    println!("{}", candidate);
+                }
+            },
+            // not a Display Name, try the internal Name, e.g. HDMI1
+            Err(Error::InvalidArgument(_)) => zone.select_input(&input.parse::<InputId>()?)?,
+            Err(err) => return Err(err)
+        }
     }
-    if let Some(matches) = matches.subcommand_matches("volume") {
+    if matches.subcommand_matches("capabilities").is_some() {
+        let capabilities = avr.get_capabilities()?;
+        if let Some(unit_name) = capabilities.unit_name {
+            println!("Model: {}", unit_name);
+        }
+        for subunit in capabilities.subunits {
+            println!("{}", subunit.tag);
+            for command in subunit.commands {
+                let mut line = format!("  {}", command.path.join("/"));
+                if let Some(range) = command.range {
+                    line.push_str(&format!(" {}..{} step {}", range.min, range.max, range.step));
+                }
+                if !command.values.is_empty() {
+                    line.push_str(&format!(" [{}]", command.values.join(", ")));
+                }
+                if !command.writable {
+                    line.push_str(" (read only)");
+                }
+                println!("{}", line);
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("direct") {
+        match matches.value_of("value") {
+            Some(value) => {
+                match parse_bool_state(value) {
+                    Some(value) => zone.set_pure_direct(value)?,
+                    None => println!("Invalid value {}", value)
+                }
+            },
+            None => {
+                match zone.get_pure_direct()? {
+                    Some(direct) => println!("Pure Direct: {}", transform_bool_state(direct)),
+                    None => println!("Pure Direct is not available")
+                }
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("drc") {
+        match matches.value_of("value") {
+            Some(value) => {
+                match parse_bool_state(value) {
+                    Some(value) => zone.set_adaptive_drc(value)?,
+                    None => println!("Invalid value {}", value)
+                }
+            },
+            None => {
+                match zone.get_adaptive_drc()? {
+                    Some(drc) => println!("Adaptive DRC: {}", transform_bool_state(drc)),
+                    None => println!("Adaptive DRC is not available")
+                }
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("hdmi") {
+        if let Some(value) = matches.value_of("standby_through") {
+            match parse_bool_state(value) {
+                Some(value) => avr.set_hdmi_standby_through(value)?,
+                None => println!("Invalid value {}", value)
+            }
+        }
+        match (matches.value_of("output"), matches.value_of("value")) {
+            (Some(output), Some(value)) => {
+                match (output.parse::<u8>(), parse_bool_state(value)) {
+                    (Ok(output), Some(value)) => zone.set_hdmi_output(output, value)?,
+                    (Err(_), _) => println!("Invalid output {}", output),
+                    (_, None) => println!("Invalid value {}", value)
+                }
+            },
+            (None, _) if matches.is_present("standby_through") => {},
+            (output, _) => {
+                if output.is_none() {
+                    if let Some(standby_through) = avr.get_hdmi_standby_through()? {
+                        println!("HDMI Standby Through: {}", transform_bool_state(standby_through));
+                    }
+                }
+                let outputs = zone.get_hdmi_outputs()?;
+                for hdmi_output in outputs {
+                    if output.map(|output| output == hdmi_output.number.to_string()).unwrap_or(true) {
+                        println!("HDMI Out {}: {}", hdmi_output.number, transform_bool_state(hdmi_output.enabled));
+                    }
+                }
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("program") {
+        if matches.is_present("list") {
+            for program in zone.sound_programs() {
+                println!("{}", program);
+            }
+        }
+        if let Some(value) = matches.value_of("value") {
+            zone.set_sound_program(value.parse()?)?;
+        }
+        if let Some(value) = matches.value_of("straight") {
+            match parse_bool_state(value) {
+                Some(value) => zone.set_straight(value)?,
+                None => println!("Invalid value {}", value)
+            }
+        }
+        if let Some(value) = matches.value_of("enhancer") {
+            match parse_bool_state(value) {
+                Some(value) => zone.set_enhancer(value)?,
+                None => println!("Invalid value {}", value)
+            }
+        }
+        if let Some(value) = matches.value_of("cinema_dsp") {
+            match parse_bool_state(value) {
+                Some(value) => zone.set_3d_cinema_dsp(value)?,
+                None => println!("Invalid value {}", value)
+            }
+        }
+        let changed = ["list", "value", "straight", "enhancer", "cinema_dsp"].iter().any(|arg| matches.is_present(arg));
+        if !changed {
+            match zone.get_sound_program()? {
+                Some(program) => println!("Sound Program: {}", program),
+                None => println!("Sound Program: None")
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("scene") {
+        match matches.value_of("scene") {
+            Some(scene) => {
+                let number = match scene.parse::<u8>() {
+                    Ok(number) => Some(number),
+                    Err(_) => zone.get_scenes()?
+                        .into_iter()
+                        .find(|candidate| candidate.name.eq_ignore_ascii_case(scene.trim()))
+                        .map(|candidate| candidate.number)
+                };
+                match number {
+                    Some(number) => zone.recall_scene(number)?,
+                    None => println!("Unknown scene {}", scene)
+                }
+            },
+            None => {
+                for scene in zone.get_scenes()? {
+                    if scene.available {
+                        println!("{}: {}", scene.number, scene.name);
+                    }else {
+                        println!("{}: {} (not available)", scene.number, scene.name);
+                    }
+                }
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("sleep") {
         match matches.value_of("value") {
             Some(value) => {
-                let parsed_value: Result<i32, std::num::ParseIntError> = value.parse();
-                if parsed_value.is_ok() {
-                    avr.set_volume(parsed_value.unwrap()).unwrap();
-                }else {
-                    println!("Invalid value {}", value);
+                match value.parse::<SleepTimer>() {
+                    Ok(value) => zone.set_sleep(value)?,
+                    Err(_) => println!("Invalid value {}", value)
                 }
             },
             None => {
-                let volume = avr.get_volume().unwrap();
-                println!("Volume: {:?}", volume);
+                let sleep = zone.get_sleep()?;
+                println!("Sleep: {}", sleep);
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("tone") {
+        let bass = matches.value_of("bass");
+        let treble = matches.value_of("treble");
+        if let Some(value) = bass {
+            match value.trim_end_matches("dB").trim().parse::<f32>() {
+                Ok(db) => zone.set_bass(db)?,
+                Err(_) => println!("Invalid value {}", value)
+            }
+        }
+        if let Some(value) = treble {
+            match value.trim_end_matches("dB").trim().parse::<f32>() {
+                Ok(db) => zone.set_treble(db)?,
+                Err(_) => println!("Invalid value {}", value)
+            }
+        }
+        if bass.is_none() && treble.is_none() {
+            match zone.get_tone()? {
+                Some(tone) => {
+                    println!("Bass: {} dB", tone.bass);
+                    println!("Treble: {} dB", tone.treble);
+                },
+                None => println!("Tone control is not available")
             }
         }
     }
+    if let Some(matches) = matches.subcommand_matches("tuner") {
+        let tuner = avr.tuner()?;
+        match matches.subcommand() {
+            ("band", Some(matches)) => {
+                match matches.value_of("value") {
+                    Some(value) => {
+                        match value.parse::<Band>() {
+                            Ok(band) => tuner.set_band(band)?,
+                            Err(_) => println!("Invalid value {}", value)
+                        }
+                    },
+                    None => println!("Band: {}", tuner.get_band()?)
+                }
+            },
+            ("frequency", Some(matches)) => {
+                match matches.value_of("value") {
+                    Some(value) => {
+                        match value.parse::<Frequency>() {
+                            Ok(frequency) => tuner.set_frequency(frequency)?,
+                            Err(_) => println!("Invalid value {}", value)
+                        }
+                    },
+                    None => println!("Frequency: {}", tuner.get_frequency()?)
+                }
+            },
+            ("seek", Some(matches)) => {
+                match matches.value_of("direction").unwrap() {
+                    "up" => tuner.seek_up()?,
+                    "down" => tuner.seek_down()?,
+                    direction => println!("Invalid direction {}", direction)
+                }
+            },
+            ("presets", Some(matches)) => {
+                let presets = if matches.is_present("dab") { tuner.get_dab_presets()? } else { tuner.get_presets()? };
+                for preset in presets {
+                    println!("{}: {}", preset.number, preset.title);
+                }
+            },
+            ("preset", Some(matches)) => {
+                let number = matches.value_of("number").unwrap();
+                match number.parse::<u8>() {
+                    Ok(number) if matches.is_present("dab") => tuner.recall_dab_preset(number)?,
+                    Ok(number) => tuner.recall_preset(number)?,
+                    Err(_) => println!("Invalid preset {}", number)
+                }
+            },
+            ("store", Some(matches)) => {
+                let number = matches.value_of("number").unwrap();
+                match number.parse::<u8>() {
+                    Ok(number) if matches.is_present("dab") => tuner.store_dab_preset(number)?,
+                    Ok(number) => tuner.store_preset(number)?,
+                    Err(_) => println!("Invalid preset {}", number)
+                }
+            },
+            ("services", Some(_)) => {
+                for service in tuner.get_dab_services()? {
+                    println!("{}: {}", service.number, service.label);
+                }
+            },
+            ("service", Some(matches)) => {
+                let service = matches.value_of("service").unwrap();
+                let number = match service.parse::<u16>() {
+                    Ok(number) => Some(number),
+                    Err(_) => tuner.get_dab_services()?
+                        .into_iter()
+                        .find(|candidate| candidate.label.eq_ignore_ascii_case(service.trim()))
+                        .map(|candidate| candidate.number)
+                };
+                match number {
+                    Some(number) => tuner.select_dab_service(number)?,
+                    None => println!("Unknown service {}", service)
+                }
+            },
+            _ => {
+                let status = tuner.get_status()?;
+                if let Some(band) = status.band {
+                    println!("Band: {}", band);
+                }
+                if let Some(frequency) = status.frequency {
+                    println!("Frequency: {}", frequency);
+                }
+                if let Some(preset) = status.preset {
+                    println!("Preset: {}", preset);
+                }
+                println!("Tuned: {}", if status.tuned { "Yes" } else { "No" });
+                println!("Stereo: {}", if status.stereo { "Yes" } else { "No" });
+                if let Some(rds) = status.rds {
+                    if let Some(program_service) = rds.program_service {
+                        println!("Station: {}", program_service);
+                    }
+                    if let Some(program_type) = rds.program_type {
+                        println!("Program Type: {}", program_type);
+                    }
+                    if let Some(radio_text) = rds.radio_text {
+                        println!("Radio Text: {}", radio_text);
+                    }
+                }
+                if let Some(dab) = status.dab {
+                    if let Some(service_label) = dab.service_label {
+                        println!("Service: {}", service_label);
+                    }
+                    if let Some(ensemble_label) = dab.ensemble_label {
+                        println!("Ensemble: {}", ensemble_label);
+                    }
+                    if let Some(dls) = dab.dls {
+                        println!("DLS: {}", dls);
+                    }
+                    if let Some(signal_quality) = dab.signal_quality {
+                        println!("Signal Quality: {}%", signal_quality);
+                    }
+                    if let Some(bit_rate) = dab.bit_rate {
+                        println!("Bit Rate: {} kbit/s", bit_rate);
+                    }
+                }
+            }
+        }
+    }
+    if let Some(matches) = matches.subcommand_matches("volume") {
+        if let Some(matches) = matches.subcommand_matches("set") {
+            let value = matches.value_of("value").unwrap();
+            match value.parse::<Volume>() {
+                Ok(value) => zone.set_volume(value)?,
+                Err(_) => println!("Invalid value {}", value)
+            }
+        }else if let Some(matches) = matches.subcommand_matches("fade") {
+            let target = matches.value_of("target").unwrap();
+            let over = matches.value_of("over").unwrap();
+            match (target.parse::<Volume>(), parse_duration(over)) {
+                (Ok(target), Some(over)) => {
+                    let volume = zone.fade_volume(target, over, &CancellationToken::new())?;
+                    println!("Volume: {}", volume);
+                },
+                (Err(_), _) => println!("Invalid value {}", target),
+                (_, None) => println!("Invalid duration {}", over)
+            }
+        }else {
+            match matches.value_of("value") {
+                Some(value) if value.starts_with('+') || value.starts_with('-') => {
+                    match value.parse::<f32>() {
+                        Ok(delta) => zone.change_volume(delta)?,
+                        Err(_) => println!("Invalid value {}", value)
+                    }
+                },
+                Some(value) => {
+                    match value.parse::<Volume>() {
+                        Ok(value) => zone.set_volume(value)?,
+                        Err(_) => println!("Invalid value {}", value)
+                    }
+                },
+                None => {
+                    let volume = zone.get_volume()?;
+                    println!("Volume: {}", volume);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Settings read from the Config File
+#[derive(Default)]
+struct Config {
+    ip: Option<String>,
+    volume_limits: VolumeLimits
+}
+
+/// Reads `key = value` Lines, a missing File is only an Error when given explicitly
+///
+/// ```text
+/// ip = 192.168.2.102
+/// max_volume = -10
+/// max_step = 6
+/// quiet_hours = 22:00-07:00
+/// quiet_volume = -40
+/// ```
+fn load_config(path: Option<&str>) -> Result<Config> {
+    let content = match path {
+        Some(path) => fs::read_to_string(path)?,
+        None => match default_config_path().map(fs::read_to_string) {
+            Some(Ok(content)) => content,
+            Some(Err(ref err)) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Some(Err(err)) => return Err(err.into()),
+            None => return Ok(Config::default())
+        }
+    };
+    parse_config(&content)
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("yamaha-avr").join("config"))
+}
+
+fn parse_config(content: &str) -> Result<Config> {
+    let mut config = Config::default();
+    let mut quiet_hours = None;
+    let mut quiet_volume = None;
+    for line in content.lines() {
+        // comments run until the end of the line, e.g. `max_volume = -10  # never go above -10 dB`
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim().trim_matches('"')),
+            None => return Err(Error::InvalidArgument(format!("Invalid config line {}", line)))
+        };
+        match key {
+            "ip" => config.ip = Some(value.to_owned()),
+            "max_volume" => config.volume_limits.max_volume = Some(value.parse()?),
+            "max_step" => {
+                let step = value.trim_end_matches("dB").trim().parse::<f32>().ok()
+                    .filter(|step| step.is_finite() && *step >= 0.0)
+                    .ok_or_else(|| Error::InvalidArgument(format!("Invalid max_step {}", value)))?;
+                config.volume_limits.max_step_db = Some(step);
+            },
+            "quiet_hours" => quiet_hours = Some(parse_time_window(value)?),
+            "quiet_volume" => quiet_volume = Some(value.parse::<Volume>()?),
+            _ => return Err(Error::InvalidArgument(format!("Unknown config key {}", key)))
+        }
+    }
+    config.volume_limits.quiet_hours = match (quiet_hours, quiet_volume) {
+        (Some((start, end)), Some(max_volume)) => Some(QuietHours { start, end, max_volume }),
+        (None, None) => None,
+        _ => return Err(Error::InvalidArgument("quiet_hours and quiet_volume have to be set together".to_owned()))
+    };
+    Ok(config)
+}
+
+/// Parses Windows like `22:00-07:00`
+fn parse_time_window(input: &str) -> Result<(NaiveTime, NaiveTime)> {
+    let invalid = || Error::InvalidArgument(format!("Invalid quiet_hours {}", input));
+    let (start, end) = input.split_once('-').ok_or_else(invalid)?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").map_err(|_| invalid())?;
+    Ok((start, end))
 }
 
 fn parse_bool_state(input: &str) -> Option<bool> {
@@ -101,6 +713,55 @@ fn parse_bool_state(input: &str) -> Option<bool> {
     None
 }
 
+/// Parses Durations like `10s`, `2m` or `500ms`, plain Numbers are Seconds
+fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    if let Some(millis) = input.strip_suffix("ms") {
+        return millis.trim().parse().ok().map(Duration::from_millis);
+    }
+    if let Some(minutes) = input.strip_suffix('m') {
+        return minutes.trim().parse::<f32>().ok().and_then(|m| Duration::try_from_secs_f32(m * 60.0).ok());
+    }
+    let seconds = input.strip_suffix('s').unwrap_or(input);
+    seconds.trim().parse::<f32>().ok().and_then(|s| Duration::try_from_secs_f32(s).ok())
+}
+
 fn transform_bool_state(input: bool) -> &'static str {
     if input { "On" } else { "Off" }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config_should_accept_readme_example() {
+        let readme = include_str!("../../README.md");
+        let section = &readme[readme.find("#### Config").unwrap()..];
+        let example = section.split("```").nth(1).unwrap();
+        let config = parse_config(example).unwrap();
+        assert_eq!(config.ip.as_deref(), Some("192.168.2.102"));
+        assert_eq!(config.volume_limits.max_volume, Some(Volume::from_db(-10.0)));
+        assert_eq!(config.volume_limits.max_step_db, Some(6.0));
+        let quiet_hours = config.volume_limits.quiet_hours.unwrap();
+        assert_eq!(quiet_hours.start, NaiveTime::from_hms_opt(22, 0, 0).unwrap());
+        assert_eq!(quiet_hours.max_volume, Volume::from_db(-40.0));
+    }
+
+    #[test]
+    fn parse_config_should_reject_invalid_values() {
+        assert!(parse_config("max_volume = loud").is_err());
+        assert!(parse_config("max_step = NaN").is_err());
+        assert!(parse_config("quiet_hours = 22:00-07:00").is_err());
+    }
+
+    #[test]
+    fn parse_duration_should_reject_invalid_values() {
+        assert_eq!(parse_duration("10s"), Some(Duration::from_secs(10)));
+        assert_eq!(parse_duration("1.5m"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        for input in &["inf", "1e30m", "-1s", "NaN", "soon"] {
+            assert_eq!(parse_duration(input), None);
+        }
+    }
+}
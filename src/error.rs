@@ -0,0 +1,116 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+use std::string::FromUtf8Error;
+use crate::yamaha::{Input, VolumeLimitViolation, Zone};
+
+pub type Result<T> = result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The Request could not be sent or the Response could not be received
+    Http(hyper::Error),
+    /// The URL of the Receiver is invalid
+    InvalidUrl(hyper::http::Error),
+    /// Network Errors outside of HTTP, e.g. during discovery
+    Io(io::Error),
+    /// The Receiver answered with a non success HTTP Status
+    Status(hyper::StatusCode),
+    /// The Response Body was not valid UTF-8
+    Utf8(FromUtf8Error),
+    /// The Response Body was not valid XML
+    Xml(xml::reader::Error),
+    /// The Response was valid XML but did not have the expected structure
+    UnexpectedResponse(String),
+    /// The Command is not available in the current state, e.g. the Zone is in Standby (`RC="1"`)
+    NotAvailable,
+    /// The Receiver does not know the Command (`RC="2"`)
+    InvalidCommand,
+    /// The Receiver does not accept the Value, e.g. an unknown Input (`RC="3"`)
+    InvalidValue,
+    /// The Receiver is busy and can not handle the Command right now (`RC="4"`)
+    Busy,
+    /// The Receiver rejected the Command with an unknown Response Code
+    Receiver(u32),
+    /// The Argument was rejected before sending it to the Receiver
+    InvalidArgument(String),
+    /// The Receiver does not have the requested Zone
+    ZoneNotAvailable(Zone),
+    /// The Unit Description of the Receiver does not contain this Function, e.g. `Zone_2`
+    Unsupported(String),
+    /// The Volume Change was rejected by the configured `VolumeLimits`
+    VolumeLimitExceeded(VolumeLimitViolation),
+    /// The Input Name matches more than one Input, contains all Candidates
+    AmbiguousInput(Vec<Input>)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Http(ref err) => write!(f, "HTTP error: {}", err),
+            Error::InvalidUrl(ref err) => write!(f, "Invalid URL: {}", err),
+            Error::Io(ref err) => write!(f, "IO error: {}", err),
+            Error::Status(ref status) => write!(f, "Unexpected HTTP status: {}", status),
+            Error::Utf8(ref err) => write!(f, "Invalid UTF-8 in response: {}", err),
+            Error::Xml(ref err) => write!(f, "Invalid XML in response: {}", err),
+            Error::UnexpectedResponse(ref msg) => write!(f, "Unexpected response: {}", msg),
+            Error::NotAvailable => write!(f, "Command is not available in the current state"),
+            Error::InvalidCommand => write!(f, "Command is not supported by the receiver"),
+            Error::InvalidValue => write!(f, "Value is not accepted by the receiver"),
+            Error::Busy => write!(f, "Receiver is busy"),
+            Error::Receiver(code) => write!(f, "Receiver rejected the command with code {}", code),
+            Error::InvalidArgument(ref msg) => write!(f, "Invalid argument: {}", msg),
+            Error::ZoneNotAvailable(zone) => write!(f, "Zone {} is not available", zone),
+            Error::Unsupported(ref function) => write!(f, "{} is not supported by the receiver", function),
+            Error::VolumeLimitExceeded(ref violation) => write!(f, "Volume limit exceeded: {}", violation),
+            Error::AmbiguousInput(ref candidates) => {
+                let candidates: Vec<String> = candidates.iter().map(ToString::to_string).collect();
+                write!(f, "Ambiguous input, candidates: {}", candidates.join(", "))
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Http(ref err) => Some(err),
+            Error::InvalidUrl(ref err) => Some(err),
+            Error::Io(ref err) => Some(err),
+            Error::Utf8(ref err) => Some(err),
+            Error::Xml(ref err) => Some(err),
+            _ => None
+        }
+    }
+}
+
+impl From<hyper::Error> for Error {
+    fn from(err: hyper::Error) -> Error {
+        Error::Http(err)
+    }
+}
+
+impl From<hyper::http::Error> for Error {
+    fn from(err: hyper::http::Error) -> Error {
+        Error::InvalidUrl(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(err: FromUtf8Error) -> Error {
+        Error::Utf8(err)
+    }
+}
+
+impl From<xml::reader::Error> for Error {
+    fn from(err: xml::reader::Error) -> Error {
+        Error::Xml(err)
+    }
+}
@@ -1,14 +1,34 @@
 extern crate xml;
-extern crate futures;
 extern crate hyper;
-extern crate tokio_core;
+extern crate tokio;
+extern crate chrono;
 
+use std::time::Duration;
+
+mod error;
 pub mod yamaha;
 
-pub fn connect(ip: String) -> yamaha::YamahaAvr {
+pub use error::{Error, Result};
+
+pub fn connect(ip: String) -> Result<yamaha::YamahaAvr> {
     yamaha::YamahaAvr::new(ip)
 }
 
-pub fn discover() -> Vec<yamaha::YamahaAvr> {
-    unimplemented!()
-}
\ No newline at end of file
+pub fn connect_async(ip: String) -> yamaha::AsyncYamahaAvr {
+    yamaha::AsyncYamahaAvr::new(ip)
+}
+
+/// Search the local network for Yamaha AVRs, waiting 3 seconds for responses.
+pub fn discover() -> Result<Vec<yamaha::YamahaAvr>> {
+    discover_with_timeout(Duration::from_secs(3))
+}
+
+/// Search the local network for Yamaha AVRs, waiting `timeout` for responses.
+pub fn discover_with_timeout(timeout: Duration) -> Result<Vec<yamaha::YamahaAvr>> {
+    yamaha::discovery::discover_blocking(timeout)
+}
+
+/// Search the local network for Yamaha AVRs, waiting `timeout` for responses.
+pub async fn discover_async(timeout: Duration) -> Result<Vec<yamaha::AsyncYamahaAvr>> {
+    yamaha::discovery::discover(timeout).await
+}
@@ -1,72 +1,153 @@
-extern crate xml;
-
-use xml::reader::XmlEvent;
-use std::io::Result;
+use crate::error::{Error, Result};
+use super::element::Element;
+use super::input::InputId;
+use super::sleep::SleepTimer;
+use super::sound_program::SoundProgram;
+use super::tone::Tone;
+use super::volume::Volume;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct BasicInfo {
     pub power: bool,
-    pub volume: i32,
+    /// None when the Zone does not report the Sleep Timer or uses a Value unknown to this crate
+    pub sleep: Option<SleepTimer>,
+    /// None when the Zone does not report a Level
+    pub volume: Option<Volume>,
     pub mute: bool,
-    pub input: String
+    /// The selected Input, None when the Zone does not report one
+    pub input: Option<InputId>,
+    /// Details about the selected Input, None when the Zone does not report them
+    pub input_info: Option<InputInfo>,
+    /// The DSP Settings, None for Zones without DSP
+    pub surround: Option<Surround>,
+    /// Tone, Direct, HDMI and DRC Settings, None for Zones without these Settings
+    pub sound_video: Option<SoundVideo>
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct InputInfo {
+    /// The Internal name of the selected Input
+    pub param: String,
+    /// A User set Display Name, None when the title is empty
+    pub title: Option<String>,
+    /// Path of the Icon shown by the Receiver, e.g. `/YamahaRemoteControl/Icons/icon004.png`
+    pub icon: Option<String>,
+    pub src_name: Option<String>,
+    pub src_number: Option<u32>
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Surround {
+    pub sound_program: Option<SoundProgram>,
+    pub straight: bool,
+    pub enhancer: bool,
+    pub cinema_dsp_3d: bool
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct SoundVideo {
+    /// None unless the Zone reports both Bass and Treble
+    pub tone: Option<Tone>,
+    pub direct: bool,
+    /// None when the Zone does not report HDMI Standby Through
+    pub hdmi_standby_through: Option<bool>,
+    pub hdmi_outputs: Vec<HdmiOutput>,
+    pub adaptive_drc: bool
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct HdmiOutput {
+    /// The Number of the Output, starting at 1
+    pub number: u8,
+    pub enabled: bool
 }
 
 pub fn parse_basic_info(xml: String) -> Result<BasicInfo> {
-    let reader = xml::reader::EventReader::from_str(&xml);
-    let mut current_element: Option<&'static str> = None;
-    let mut in_val: bool = false;
-    let mut basic_info = BasicInfo {
-        power: false,
-        volume: 0,
-        mute: false,
-        input: "".to_owned()
+    let root = Element::parse(&xml)?;
+    // The Zone Element varies, e.g. Main_Zone or Zone_2
+    let status = root.children().iter()
+        .find_map(|zone| zone.child("Basic_Status"))
+        .ok_or_else(|| Error::UnexpectedResponse("Missing Basic_Status".to_owned()))?;
+    let volume = match status.find("Volume/Lvl") {
+        Some(level) => Volume::from_level(level)?,
+        None => None
+    };
+    Ok(BasicInfo {
+        power: status.find_text("Power_Control/Power") == Some("On"),
+        sleep: status.find_text("Power_Control/Sleep").and_then(|sleep| sleep.parse().ok()),
+        volume,
+        mute: status.find_text("Volume/Mute") == Some("On"),
+        input: status.find_text("Input/Input_Sel").and_then(|input| input.parse().ok()),
+        input_info: status.find("Input/Input_Sel_Item_Info").map(parse_input_info).transpose()?,
+        surround: status.child("Surround").map(parse_surround),
+        sound_video: status.child("Sound_Video").map(parse_sound_video).transpose()?
+    })
+}
+
+fn parse_input_info(info: &Element) -> Result<InputInfo> {
+    Ok(InputInfo {
+        param: info.find_text("Param").unwrap_or_default().to_owned(),
+        title: non_empty(info.find_text("Title")),
+        icon: non_empty(info.find_text("Icon/On")),
+        src_name: non_empty(info.find_text("Src_Name")),
+        src_number: info.find_parsed("Src_Number")?
+    })
+}
+
+fn parse_surround(surround: &Element) -> Surround {
+    Surround {
+        sound_program: non_empty(surround.find_text("Program_Sel/Current/Sound_Program"))
+            .map(|program| SoundProgram::from_value(&program)),
+        straight: surround.find_text("Program_Sel/Current/Straight") == Some("On"),
+        enhancer: surround.find_text("Program_Sel/Current/Enhancer") == Some("On"),
+        cinema_dsp_3d: surround.find_text("_3D_Cinema_DSP").map(|s| s != "Off").unwrap_or(false)
+    }
+}
+
+fn parse_sound_video(sound_video: &Element) -> Result<SoundVideo> {
+    let hdmi_outputs = match sound_video.find("HDMI/Output") {
+        Some(outputs) => outputs.children().iter()
+            .filter_map(|output| output.name().strip_prefix("OUT_").map(|number| (number, output)))
+            .map(|(number, output)| {
+                let number = number.parse()
+                    .map_err(|_| Error::UnexpectedResponse(format!("Invalid HDMI output {}", output.name())))?;
+                Ok(HdmiOutput {
+                    number,
+                    enabled: output.text() == "On"
+                })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        None => Vec::new()
     };
+    Ok(SoundVideo {
+        tone: sound_video.child("Tone").map(parse_tone).transpose()?.flatten(),
+        direct: sound_video.find_text("Direct/Mode") == Some("On"),
+        hdmi_standby_through: sound_video.find_text("HDMI/Standby_Through_Info").map(|value| value == "On"),
+        hdmi_outputs,
+        adaptive_drc: sound_video.find_text("Adaptive_DRC").map(|s| s != "Off").unwrap_or(false)
+    })
+}
+
+/// None when neither Bass nor Treble are reported
+fn parse_tone(tone: &Element) -> Result<Option<Tone>> {
+    let level = |name| match tone.child(name) {
+        Some(level) => Volume::from_level(level),
+        None => Ok(None)
+    };
+    // A Tone is only complete with both Levels
+    match (level("Bass")?, level("Treble")?) {
+        (Some(bass), Some(treble)) => Ok(Some(Tone { bass: bass.db(), treble: treble.db() })),
+        _ => Ok(None)
+    }
+}
 
-    for element in reader {
-        match element {
-            Ok(XmlEvent::StartElement { name, .. }) => {
-                match name.local_name.as_str() {
-                    "Power" => current_element = Some("Power"),
-                    "Volume" => current_element = Some("Volume"),
-                    "Mute" => current_element = Some("Mute"),
-                    "Input_Sel" => current_element = Some("Input"),
-                    "Val" => in_val = true,
-                    _ => {}
-                }
-            }
-            Ok(XmlEvent::EndElement { name, .. }) => {
-                match name.local_name.as_str() {
-                    "Power" => current_element = None,
-                    "Volume" => current_element = None,
-                    "Mute" => current_element = None,
-                    "Input_Sel" => current_element = None,
-                    "Val" => in_val = false,
-                    _ => {}
-                }
-            }
-            Ok(XmlEvent::Characters(s)) => {
-                match current_element {
-                    Some("Power") => {
-                        basic_info.power = if s == "On" { true } else { false };
-                    }
-                    Some("Mute") => {
-                        basic_info.mute = if s == "On" { true } else { false };
-                    }
-                    Some("Input") => {
-                        basic_info.input = s;
-                    }
-                    Some("Volume") => {
-                        if in_val {
-                            basic_info.volume = s.parse().unwrap();
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            _ => {}
-        }
+fn non_empty(s: Option<&str>) -> Option<String> {
+    let s = s?.trim();
+    if s.is_empty() {
+        None
+    }else {
+        Some(s.to_owned())
     }
-    return Ok(basic_info);
 }
 
 #[cfg(test)]
@@ -78,9 +159,84 @@ mod tests {
         let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Power_Control><Power>On</Power><Sleep>Off</Sleep></Power_Control><Volume><Lvl><Val>-300</Val><Exp>1</Exp><Unit>dB</Unit></Lvl><Mute>Off</Mute></Volume><Input><Input_Sel>HDMI1</Input_Sel><Input_Sel_Item_Info><Param>HDMI1</Param><RW>RW</RW><Title>  Chrome </Title><Icon><On>/YamahaRemoteControl/Icons/icon004.png</On><Off></Off></Icon><Src_Name></Src_Name><Src_Number>1</Src_Number></Input_Sel_Item_Info></Input><Surround><Program_Sel><Current><Straight>Off</Straight><Enhancer>Off</Enhancer><Sound_Program>5ch Stereo</Sound_Program></Current></Program_Sel><_3D_Cinema_DSP>Off</_3D_Cinema_DSP></Surround><Sound_Video><Tone><Bass><Val>0</Val><Exp>1</Exp><Unit>dB</Unit></Bass><Treble><Val>0</Val><Exp>1</Exp><Unit>dB</Unit></Treble></Tone><Direct><Mode>Off</Mode></Direct><HDMI><Standby_Through_Info>On</Standby_Through_Info><Output><OUT_1>On</OUT_1></Output></HDMI><Adaptive_DRC>Off</Adaptive_DRC></Sound_Video></Basic_Status></Main_Zone></YAMAHA_AV>");
         assert_eq!(parse_basic_info(input).unwrap(), BasicInfo {
             power: true,
-            volume: -300,
+            sleep: Some(SleepTimer::Off),
+            volume: Some(Volume::from_db(-30.0)),
             mute: false,
-            input: "HDMI1".to_owned()
+            input: Some(InputId::Hdmi(1)),
+            input_info: Some(InputInfo {
+                param: "HDMI1".to_owned(),
+                title: Some("Chrome".to_owned()),
+                icon: Some("/YamahaRemoteControl/Icons/icon004.png".to_owned()),
+                src_name: None,
+                src_number: Some(1)
+            }),
+            surround: Some(Surround {
+                sound_program: Some(SoundProgram::Stereo5ch),
+                straight: false,
+                enhancer: false,
+                cinema_dsp_3d: false
+            }),
+            sound_video: Some(SoundVideo {
+                tone: Some(Tone { bass: 0.0, treble: 0.0 }),
+                direct: false,
+                hdmi_standby_through: Some(true),
+                hdmi_outputs: vec![HdmiOutput { number: 1, enabled: true }],
+                adaptive_drc: false
+            })
         });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_basic_info_should_fail_on_invalid_volume() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Volume><Lvl><Val>loud</Val><Exp>1</Exp><Unit>dB</Unit></Lvl></Volume></Basic_Status></Main_Zone></YAMAHA_AV>");
+        assert!(parse_basic_info(input).is_err());
+    }
+
+    #[test]
+    fn parse_basic_info_should_only_read_volume_level() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Zone_2><Basic_Status><Power_Control><Power>Standby</Power><Sleep>60 min</Sleep></Power_Control><Volume><Lvl><Val>-455</Val><Exp>1</Exp><Unit>dB</Unit></Lvl><Mute>On</Mute><Max_Lvl><Val>165</Val><Exp>1</Exp><Unit>dB</Unit></Max_Lvl></Volume><Input><Input_Sel>AV1</Input_Sel></Input></Basic_Status></Zone_2></YAMAHA_AV>");
+        assert_eq!(parse_basic_info(input).unwrap(), BasicInfo {
+            power: false,
+            sleep: Some(SleepTimer::Min60),
+            volume: Some(Volume::from_db(-45.5)),
+            mute: true,
+            input: Some(InputId::Av(1)),
+            input_info: None,
+            surround: None,
+            sound_video: None
+        });
+    }
+
+    #[test]
+    fn parse_basic_info_should_apply_tone_exponent() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Sound_Video><Tone><Bass><Val>-35</Val><Exp>1</Exp><Unit>dB</Unit></Bass><Treble><Val>20</Val><Exp>1</Exp><Unit>dB</Unit></Treble></Tone></Sound_Video></Basic_Status></Main_Zone></YAMAHA_AV>");
+        let sound_video = parse_basic_info(input).unwrap().sound_video.unwrap();
+        assert_eq!(sound_video.tone, Some(Tone { bass: -3.5, treble: 2.0 }));
+    }
+
+    #[test]
+    fn parse_basic_info_should_keep_unknown_inputs() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Input><Input_Sel>Deezer</Input_Sel></Input></Basic_Status></Main_Zone></YAMAHA_AV>");
+        assert_eq!(parse_basic_info(input).unwrap().input, Some(InputId::Other("Deezer".to_owned())));
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Input><Input_Sel></Input_Sel></Input></Basic_Status></Main_Zone></YAMAHA_AV>");
+        assert_eq!(parse_basic_info(input).unwrap().input, None);
+    }
+
+    #[test]
+    fn parse_basic_info_should_not_guess_missing_levels() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Volume><Lvl><Val></Val><Exp>1</Exp><Unit>dB</Unit></Lvl></Volume><Sound_Video><Tone><Bass><Val>-35</Val><Exp>1</Exp><Unit>dB</Unit></Bass></Tone></Sound_Video></Basic_Status></Main_Zone></YAMAHA_AV>");
+        let info = parse_basic_info(input).unwrap();
+        assert_eq!(info.volume, None);
+        assert_eq!(info.sound_video.unwrap().tone, None);
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Power_Control><Power>On</Power></Power_Control></Basic_Status></Main_Zone></YAMAHA_AV>");
+        assert_eq!(parse_basic_info(input).unwrap().volume, None);
+    }
+
+    #[test]
+    fn parse_basic_info_should_keep_unknown_sleep_timers_local() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Power_Control><Power>On</Power><Sleep>Last</Sleep></Power_Control></Basic_Status></Main_Zone></YAMAHA_AV>");
+        let info = parse_basic_info(input).unwrap();
+        assert!(info.power);
+        assert_eq!(info.sleep, None);
+    }
+}
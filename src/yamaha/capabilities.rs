@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use crate::error::{Error, Result};
+use super::command::{Command, Method, Value};
+use super::element::Element;
+use super::sound_program::SoundProgram;
+use super::volume::{Volume, VolumeRange};
+use super::zone::Zone;
+
+/// The Functions a Receiver supports according to its Unit Description (`/YamahaRemoteControl/desc.xml`)
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Capabilities {
+    /// The Model, e.g. `RX-V473`
+    pub unit_name: Option<String>,
+    pub version: Option<String>,
+    pub subunits: Vec<Subunit>
+}
+
+/// A top level Function Block like `System`, `Main_Zone` or `Tuner`
+#[derive(PartialEq, Debug, Clone)]
+pub struct Subunit {
+    /// The Tag used in Commands, e.g. `Main_Zone`
+    pub tag: String,
+    pub title: Option<String>,
+    pub commands: Vec<CommandSpec>
+}
+
+/// A single Command Path with the Values it accepts
+#[derive(PartialEq, Debug, Clone)]
+pub struct CommandSpec {
+    /// e.g. `["Main_Zone", "Volume", "Lvl"]`
+    pub path: Vec<String>,
+    /// The accepted Values of Text Commands, empty when any Value is allowed
+    pub values: Vec<String>,
+    /// The accepted `Val` of Number Commands
+    pub range: Option<ValueRange>,
+    /// The `Exp` of Number Commands, e.g. 1 when `Val` is given in tenths
+    pub exp: Option<u32>,
+    /// False for Commands which can only be queried
+    pub writable: bool
+}
+
+/// A Range of raw `Val` Values, before applying the Exponent
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct ValueRange {
+    pub min: i32,
+    pub max: i32,
+    pub step: i32
+}
+
+impl ValueRange {
+    pub fn contains(&self, val: i32) -> bool {
+        val >= self.min && val <= self.max && (self.step <= 0 || (val - self.min) % self.step == 0)
+    }
+}
+
+impl Capabilities {
+    pub fn subunit(&self, tag: &str) -> Option<&Subunit> {
+        self.subunits.iter().find(|subunit| subunit.tag == tag)
+    }
+
+    /// The Zones described by the Receiver
+    pub fn zones(&self) -> Vec<Zone> {
+        self.subunits.iter()
+            .filter_map(|subunit| Zone::from_tag(&subunit.tag))
+            .collect()
+    }
+
+    pub fn command(&self, path: &[&str]) -> Option<&CommandSpec> {
+        self.subunit(path.first()?)?.commands.iter()
+            .find(|command| command.path.iter().map(String::as_str).eq(path.iter().cloned()))
+    }
+
+    pub fn supports(&self, path: &[&str]) -> bool {
+        self.command(path).is_some()
+    }
+
+    /// The DSP Programs of a Zone as described for `<Zone>/Surround/Program_Sel/Current/Sound_Program`
+    pub fn sound_programs(&self, zone: Zone) -> Option<Vec<SoundProgram>> {
+        let command = self.command(&[zone.tag(), "Surround", "Program_Sel", "Current", "Sound_Program"])?;
+        if command.values.is_empty() {
+            return None;
+        }
+        Some(command.values.iter().map(|value| SoundProgram::from_value(value)).collect())
+    }
+
+    /// The Volume Range of a Zone as described for `<Zone>/Volume/Lvl`
+    pub fn volume_range(&self, zone: Zone) -> Option<VolumeRange> {
+        let command = self.command(&[zone.tag(), "Volume", "Lvl"])?;
+        let range = command.range?;
+        let exp = command.exp.unwrap_or(1);
+        let min = Volume::from_raw(range.min, exp, "dB").ok()?;
+        let max = Volume::from_raw(range.max, exp, "dB").ok()?;
+        VolumeRange::new(min, max).ok()
+    }
+
+    /// Check a PUT Command against the Description before sending it
+    ///
+    /// Commands of unknown Subunits fail with `Error::Unsupported`, Values outside of the described
+    /// Values or Ranges with `Error::InvalidArgument`. Commands missing from a known Subunit are not checked,
+    /// as the Descriptions do not list every Command a Receiver accepts.
+    pub(crate) fn validate(&self, command: &Command) -> Result<()> {
+        if command.method() != Method::Put {
+            return Ok(());
+        }
+        let path: Vec<&str> = command.path().iter().map(String::as_str).collect();
+        if self.subunit(path[0]).is_none() {
+            return Err(Error::Unsupported(path[0].to_owned()));
+        }
+        let spec = match self.command(&path) {
+            Some(spec) => spec,
+            None => return Ok(())
+        };
+        let invalid = |value: &str| Error::InvalidArgument(format!("{} is not supported for {}", value, path.join("/")));
+        match *command.value() {
+            Value::Text(ref text) if !spec.values.is_empty() && !spec.values.contains(text) => Err(invalid(text)),
+            Value::Level { ref val, .. } => {
+                match (val.parse::<i32>(), spec.range) {
+                    (Ok(number), Some(range)) if !range.contains(number) => Err(invalid(val)),
+                    _ => Ok(())
+                }
+            },
+            _ => Ok(())
+        }
+    }
+}
+
+pub fn parse_capabilities(xml: String) -> Result<Capabilities> {
+    let root = Element::parse(&xml)?;
+    if root.name() != "Unit_Description" {
+        return Err(Error::UnexpectedResponse(format!("Unexpected root element {}", root.name())));
+    }
+    let subunits = root.children().iter()
+        .filter(|menu| menu.name() == "Menu")
+        .filter_map(|menu| menu.attribute("YNC_Tag").map(|tag| (tag, menu)))
+        .map(|(tag, menu)| {
+            let mut commands = Vec::new();
+            collect_commands(menu, &HashMap::new(), &mut commands)?;
+            Ok(Subunit {
+                tag: tag.to_owned(),
+                title: menu.attribute("Title_1").map(str::to_owned),
+                commands
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Capabilities {
+        unit_name: root.attribute("Unit_Name").map(str::to_owned),
+        version: root.attribute("Version").map(str::to_owned),
+        subunits
+    })
+}
+
+/// Walk a Menu, `Cmd_List` Definitions are visible to the Menu and all nested Menus
+fn collect_commands(menu: &Element, defines: &HashMap<String, usize>, commands: &mut Vec<CommandSpec>) -> Result<()> {
+    let mut defines = defines.clone();
+    for define in menu.child("Cmd_List").map(Element::children).unwrap_or_default() {
+        let id = match define.attribute("ID") {
+            Some(id) => id.to_owned(),
+            None => continue
+        };
+        let path: Vec<String> = define.text().split(',').map(|element| element.trim().to_owned()).collect();
+        let index = match commands.iter().position(|command| command.path == path) {
+            Some(index) => index,
+            None => {
+                commands.push(CommandSpec {
+                    path,
+                    values: Vec::new(),
+                    range: None,
+                    exp: None,
+                    writable: false
+                });
+                commands.len() - 1
+            }
+        };
+        defines.insert(id, index);
+    }
+    for child in menu.children() {
+        if child.name() == "Menu" {
+            collect_commands(child, &defines, commands)?;
+        }else if child.name().starts_with("Put_") {
+            let cmd = match child.child("Cmd") {
+                Some(cmd) => cmd,
+                None => continue
+            };
+            let command = match cmd.attribute("ID").and_then(|id| defines.get(id)) {
+                Some(index) => &mut commands[*index],
+                None => continue
+            };
+            command.writable = true;
+            apply_put(child, cmd.text().trim(), command)?;
+        }
+    }
+    Ok(())
+}
+
+/// A `Put_n` either sends a fixed Value, e.g. `On`, or refers to its Parameters
+///
+/// Templates list the Parameters either by Position, e.g. `Param_1,Param_2,Param_3`,
+/// or by Name, e.g. `Val=Param_1:Exp=Param_2:Unit=Param_3`.
+fn apply_put(put: &Element, template: &str, command: &mut CommandSpec) -> Result<()> {
+    let parts = template_parts(template);
+    let named = parts.iter().any(|(name, _)| name.is_some());
+    let part = |name: &str, position: usize| {
+        if named {
+            parts.iter().find(|(part, _)| *part == Some(name)).map(|(_, value)| *value)
+        }else {
+            parts.get(position).map(|(_, value)| *value)
+        }
+    };
+    if let Some(exp) = part("Exp", 1).and_then(|exp| put.child(exp)).and_then(|param| param.child("Direct")) {
+        command.exp = exp.text().trim().parse().ok();
+    }
+    let param = match part("Val", 0) {
+        Some(param) if param.starts_with("Param_") => param,
+        val => {
+            let literal = if named { val.unwrap_or_default() } else { template };
+            if !literal.is_empty() && !command.values.iter().any(|value| value == literal) {
+                command.values.push(literal.to_owned());
+            }
+            return Ok(());
+        }
+    };
+    let param = match put.child(param) {
+        Some(param) => param,
+        None => return Ok(())
+    };
+    for value in param.children() {
+        match value.name() {
+            "Direct" => {
+                let text = value.text().trim();
+                if !command.values.iter().any(|value| value == text) {
+                    command.values.push(text.to_owned());
+                }
+            },
+            "Range" => command.range = Some(parse_range(value.text())?),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Splits `Val=Param_1:Exp=Param_2` into named and `Param_1,Param_2` into positional Parts
+fn template_parts(template: &str) -> Vec<(Option<&str>, &str)> {
+    if template.contains('=') {
+        template.split(':')
+            .filter_map(|part| part.split_once('='))
+            .map(|(name, value)| (Some(name.trim()), value.trim()))
+            .collect()
+    }else {
+        template.split(',').map(|part| (None, part.trim())).collect()
+    }
+}
+
+/// Parses Ranges like `-805,165,5`
+fn parse_range(text: &str) -> Result<ValueRange> {
+    let parts = text.split(',')
+        .map(|part| part.trim().parse::<i32>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| Error::UnexpectedResponse(format!("Invalid range {}", text)))?;
+    match parts.as_slice() {
+        [min, max, step] if min <= max => Ok(ValueRange { min: *min, max: *max, step: *step }),
+        [min, max] if min <= max => Ok(ValueRange { min: *min, max: *max, step: 1 }),
+        _ => Err(Error::UnexpectedResponse(format!("Invalid range {}", text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?><Unit_Description Version=\"1.2\" Unit_Name=\"RX-V473\"><Language Code=\"en\"/><Menu Func=\"Unit\" Title_1=\"System\" YNC_Tag=\"System\"><Cmd_List><Define ID=\"P1\">System,Config</Define></Cmd_List></Menu><Menu Func=\"Subunit\" Title_1=\"Main Zone\" YNC_Tag=\"Main_Zone\"><Cmd_List><Define ID=\"P1\">Main_Zone,Power_Control,Power</Define><Define ID=\"P2\">Main_Zone,Volume,Lvl</Define><Define ID=\"P3\">Main_Zone,Input,Input_Sel</Define><Define ID=\"P4\">Main_Zone,Basic_Status</Define></Cmd_List><Menu Func=\"Function\" Title_1=\"Power\"><Put_1 Func=\"Event_On\"><Cmd ID=\"P1\" Type=\"Text\">On</Cmd></Put_1><Put_2 Func=\"Event_Off\"><Cmd ID=\"P1\" Type=\"Text\">Standby</Cmd></Put_2></Menu><Menu Func=\"Function\" Title_1=\"Volume\"><Put_1 Func=\"Lvl\"><Cmd ID=\"P2\" Type=\"Number\">Param_1,Param_2,Param_3</Cmd><Param_1><Range>-805,165,5</Range></Param_1><Param_2><Direct>1</Direct></Param_2><Param_3><Direct>dB</Direct></Param_3></Put_1></Menu><Menu Func=\"Function\" Title_1=\"Input\"><Put_1 Func=\"Input_Sel\"><Cmd ID=\"P3\" Type=\"Text\">Param_1</Cmd><Param_1><Direct>HDMI1</Direct><Direct>HDMI2</Direct><Direct>TUNER</Direct></Param_1></Put_1></Menu></Menu></Unit_Description>";
+
+    #[test]
+    fn parse_capabilities_should_collect_commands() {
+        let capabilities = parse_capabilities(DESCRIPTION.to_owned()).unwrap();
+        assert_eq!(capabilities.unit_name.as_deref(), Some("RX-V473"));
+        assert_eq!(capabilities.zones(), vec![Zone::Main]);
+        let power = capabilities.command(&["Main_Zone", "Power_Control", "Power"]).unwrap();
+        assert_eq!(power.values, vec!["On", "Standby"]);
+        let volume = capabilities.command(&["Main_Zone", "Volume", "Lvl"]).unwrap();
+        assert_eq!(volume.range, Some(ValueRange { min: -805, max: 165, step: 5 }));
+        assert_eq!(volume.exp, Some(1));
+        assert_eq!(capabilities.volume_range(Zone::Main), VolumeRange::new(Volume::from_db(-80.5), Volume::from_db(16.5)).ok());
+        assert_eq!(capabilities.volume_range(Zone::Zone2), None);
+        assert!(!capabilities.command(&["Main_Zone", "Basic_Status"]).unwrap().writable);
+        assert!(!capabilities.supports(&["Main_Zone", "Scene", "Scene_Sel"]));
+    }
+
+    #[test]
+    fn parse_capabilities_should_read_named_templates() {
+        let description = "<Unit_Description Version=\"1.2\" Unit_Name=\"RX-V475\"><Menu Func=\"Subunit\" Title_1=\"Main Zone\" YNC_Tag=\"Main_Zone\"><Cmd_List><Define ID=\"P1\">Main_Zone,Volume,Lvl</Define><Define ID=\"P2\">Main_Zone,Sound_Video,Tone,Bass</Define></Cmd_List><Menu Func=\"Function\" Title_1=\"Volume\"><Put_1 Func=\"Lvl\"><Cmd ID=\"P1\" Type=\"Number\">Val=Param_1:Exp=Param_2:Unit=Param_3</Cmd><Param_1 Func=\"Number\"><Range>-805,165,5</Range></Param_1><Param_2 Func=\"Number\"><Direct>1</Direct></Param_2><Param_3 Func=\"Number\"><Direct>dB</Direct></Param_3></Put_1><Put_2 Func=\"Lvl\"><Cmd ID=\"P1\" Type=\"Text\">Val=Up 1 dB:Exp=:Unit=</Cmd></Put_2></Menu><Menu Func=\"Function\" Title_1=\"Bass\"><Put_1 Func=\"Bass\"><Cmd ID=\"P2\" Type=\"Number\">Val=Param_1:Exp=Param_2:Unit=Param_3</Cmd><Param_1><Range>-60,60,5</Range></Param_1><Param_2><Direct>1</Direct></Param_2><Param_3><Direct>dB</Direct></Param_3></Put_1></Menu></Menu></Unit_Description>";
+        let capabilities = parse_capabilities(description.to_owned()).unwrap();
+        let volume = capabilities.command(&["Main_Zone", "Volume", "Lvl"]).unwrap();
+        assert_eq!(volume.range, Some(ValueRange { min: -805, max: 165, step: 5 }));
+        assert_eq!(volume.exp, Some(1));
+        assert_eq!(volume.values, vec!["Up 1 dB"]);
+        let level = |val: &str| Value::Level { val: val.to_owned(), exp: Some(1), unit: Some("dB".to_owned()) };
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Volume", "Lvl"], level("200"))).is_err());
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Sound_Video", "Tone", "Bass"], level("-70"))).is_err());
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Sound_Video", "Tone", "Bass"], level("-35"))).is_ok());
+    }
+
+    #[test]
+    fn validate_should_check_values() {
+        let capabilities = parse_capabilities(DESCRIPTION.to_owned()).unwrap();
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Input", "Input_Sel"], "HDMI2")).is_ok());
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Input", "Input_Sel"], "AV9")).is_err());
+        let level = |val: &str| Value::Level { val: val.to_owned(), exp: Some(1), unit: Some("dB".to_owned()) };
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Volume", "Lvl"], level("-355"))).is_ok());
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Volume", "Lvl"], level("200"))).is_err());
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Volume", "Lvl"], level("-353"))).is_err());
+    }
+
+    #[test]
+    fn validate_should_reject_unknown_subunits() {
+        let capabilities = parse_capabilities(DESCRIPTION.to_owned()).unwrap();
+        match capabilities.validate(&Command::put(&["Zone_2", "Volume", "Mute"], "On")) {
+            Err(Error::Unsupported(_)) => {},
+            other => panic!("Expected Unsupported, got {:?}", other)
+        }
+        assert!(capabilities.validate(&Command::put(&["Main_Zone", "Scene", "Scene_Sel"], "Scene 1")).is_ok());
+        assert!(capabilities.validate(&Command::get(&["Zone_2", "Basic_Status"])).is_ok());
+    }
+
+    #[test]
+    fn sound_programs_should_use_described_values() {
+        let description = "<Unit_Description Version=\"1.2\" Unit_Name=\"RX-V373\"><Menu Func=\"Subunit\" Title_1=\"Main Zone\" YNC_Tag=\"Main_Zone\"><Cmd_List><Define ID=\"P1\">Main_Zone,Surround,Program_Sel,Current,Sound_Program</Define></Cmd_List><Menu Func=\"Function\" Title_1=\"Sound Program\"><Put_1 Func=\"Sound_Program\"><Cmd ID=\"P1\" Type=\"Text\">Param_1</Cmd><Param_1><Direct>Hall in Vienna</Direct><Direct>2ch Stereo</Direct><Direct>Drama 2</Direct></Param_1></Put_1></Menu></Menu></Unit_Description>";
+        let capabilities = parse_capabilities(description.to_owned()).unwrap();
+        assert_eq!(capabilities.sound_programs(Zone::Main), Some(vec![
+            SoundProgram::HallInVienna,
+            SoundProgram::Stereo2ch,
+            SoundProgram::Other("Drama 2".to_owned())
+        ]));
+        assert_eq!(parse_capabilities(DESCRIPTION.to_owned()).unwrap().sound_programs(Zone::Main), None);
+    }
+
+    #[test]
+    fn parse_range_should_reject_inverted_ranges() {
+        assert_eq!(parse_range("-805,165,5").unwrap(), ValueRange { min: -805, max: 165, step: 5 });
+        assert!(parse_range("165,-805,5").is_err());
+    }
+}
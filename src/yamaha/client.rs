@@ -0,0 +1,380 @@
+use crate::error::{Error, Result};
+use std::sync::Arc;
+use super::capabilities::{self, Capabilities};
+use super::command::{Command, Value};
+use super::features::{Feature, Features};
+use super::http::HttpClient;
+use super::input::{self, InputId};
+use super::device_info::DeviceInfo;
+use super::tuner::AsyncTuner;
+use super::zone::{AsyncZoneControl, Zone};
+use std::time::Duration;
+use super::limits::VolumeLimits;
+use super::volume::{CancellationToken, Volume, VolumeRange, VolumeStep};
+use super::{basic_info, response, system_config};
+
+/// Async Client for a single Receiver
+///
+/// All Commands share one HTTP Client, so the connection to the Receiver is kept alive between Commands.
+#[derive(Clone)]
+pub struct AsyncYamahaAvr {
+    ip: String,
+    control_url: String,
+    desc_url: String,
+    device_info: Option<DeviceInfo>,
+    volume_range: Option<VolumeRange>,
+    volume_limits: VolumeLimits,
+    capabilities: Option<Arc<Capabilities>>,
+    client: HttpClient
+}
+
+impl AsyncYamahaAvr {
+    pub fn new(ip: String) -> AsyncYamahaAvr {
+        let control_url = format!("http://{}/YamahaRemoteControl/ctrl", ip);
+        let desc_url = format!("http://{}/YamahaRemoteControl/desc.xml", ip);
+        AsyncYamahaAvr {
+            ip,
+            control_url,
+            desc_url,
+            device_info: None,
+            volume_range: None,
+            volume_limits: VolumeLimits::default(),
+            capabilities: None,
+            client: HttpClient::new()
+        }
+    }
+
+    pub(crate) fn with_device_info(ip: String, control_url: String, desc_url: String, device_info: DeviceInfo) -> AsyncYamahaAvr {
+        AsyncYamahaAvr {
+            ip,
+            control_url,
+            desc_url,
+            device_info: Some(device_info),
+            volume_range: None,
+            volume_limits: VolumeLimits::default(),
+            capabilities: None,
+            client: HttpClient::new()
+        }
+    }
+
+    pub fn ip(&self) -> &str {
+        &self.ip
+    }
+
+    /// The UPnP Device Description as advertised during discovery, None when connected manually
+    pub fn device_info(&self) -> Option<&DeviceInfo> {
+        self.device_info.as_ref()
+    }
+
+    pub fn model_name(&self) -> Option<&str> {
+        self.device_info.as_ref().map(|info| info.model_name.as_ref())
+    }
+
+    /// The URL commands are sent to
+    pub fn control_url(&self) -> &str {
+        &self.control_url
+    }
+
+    /// The Range `set_volume` clamps to in the Main_Zone, see `zone_volume_range`
+    pub fn volume_range(&self) -> VolumeRange {
+        self.zone_volume_range(Zone::Main)
+    }
+
+    /// The Range of the Zone: the Range given to `set_volume_range`, else the Range of the loaded
+    /// Unit Description, else the default Range of -80.5 dB to 16.5 dB
+    pub fn zone_volume_range(&self, zone: Zone) -> VolumeRange {
+        self.volume_range
+            .or_else(|| self.capabilities.as_ref().and_then(|capabilities| capabilities.volume_range(zone)))
+            .unwrap_or_default()
+    }
+
+    /// Override the Range of all Zones, takes precedence over the Unit Description
+    pub fn set_volume_range(&mut self, range: VolumeRange) {
+        self.volume_range = Some(range);
+    }
+
+    /// The Limits all Volume Changes are checked against
+    pub fn volume_limits(&self) -> VolumeLimits {
+        self.volume_limits
+    }
+
+    /// Reject Volume Changes exceeding the given Limits with `Error::VolumeLimitExceeded`
+    pub fn set_volume_limits(&mut self, limits: VolumeLimits) {
+        self.volume_limits = limits;
+    }
+
+    /// The URL of the Unit Description
+    pub fn desc_url(&self) -> &str {
+        &self.desc_url
+    }
+
+    /// The cached Unit Description, None until `load_capabilities` was called
+    pub fn capabilities(&self) -> Option<&Capabilities> {
+        self.capabilities.as_deref()
+    }
+
+    /// Fetch and parse the Unit Description without caching it
+    pub async fn get_capabilities(&self) -> Result<Capabilities> {
+        let res = self.client.get(&self.desc_url).await?;
+        capabilities::parse_capabilities(res)
+    }
+
+    /// Fetch the Unit Description and cache it, all following Commands are validated against it
+    ///
+    /// Calling it again replaces the cached Description, e.g. after a Firmware Update.
+    pub async fn load_capabilities(&mut self) -> Result<&Capabilities> {
+        let capabilities = self.get_capabilities().await?;
+        Ok(self.capabilities.insert(Arc::new(capabilities)))
+    }
+
+    pub(crate) async fn exec(&self, command: Command) -> Result<String> {
+        command.check_path()?;
+        if let Some(ref capabilities) = self.capabilities {
+            capabilities.validate(&command)?;
+        }
+        let res = self.client.exec(&self.control_url, command.to_xml()).await?;
+        response::check_response_code(&res)?;
+        Ok(res)
+    }
+
+    pub fn main_zone(&self) -> AsyncZoneControl<'_> {
+        AsyncZoneControl::new(self, Zone::Main)
+    }
+
+    /// Control the given Zone
+    ///
+    /// Fails with `Error::ZoneNotAvailable` when the Receiver does not have this Zone,
+    /// uses the cached Unit Description when available instead of querying the System Config
+    pub async fn zone(&self, zone: Zone) -> Result<AsyncZoneControl<'_>> {
+        let available = match self.capabilities {
+            Some(ref capabilities) => capabilities.zones().contains(&zone),
+            None => self.get_zones().await?.iter().any(|available| available == zone.tag())
+        };
+        if !available {
+            return Err(Error::ZoneNotAvailable(zone));
+        }
+        Ok(AsyncZoneControl::new(self, zone))
+    }
+
+    pub async fn get_basic_info(&self) -> Result<basic_info::BasicInfo> {
+        self.main_zone().get_basic_info().await
+    }
+
+    pub async fn get_power(&self) -> Result<bool> {
+        self.main_zone().get_power().await
+    }
+
+    pub async fn set_power(&self, value: bool) -> Result<()> {
+        self.main_zone().set_power(value).await
+    }
+
+    pub async fn get_mute(&self) -> Result<bool> {
+        self.main_zone().get_mute().await
+    }
+
+    pub async fn set_mute(&self, value: bool) -> Result<()> {
+        self.main_zone().set_mute(value).await
+    }
+
+    pub async fn get_volume(&self) -> Result<Volume> {
+        self.main_zone().get_volume().await
+    }
+
+    /// Set the Volume, values outside of `volume_range` are clamped
+    pub async fn set_volume(&self, value: Volume) -> Result<()> {
+        self.main_zone().set_volume(value).await
+    }
+
+    pub async fn volume_up(&self, step: VolumeStep) -> Result<()> {
+        self.main_zone().volume_up(step).await
+    }
+
+    pub async fn volume_down(&self, step: VolumeStep) -> Result<()> {
+        self.main_zone().volume_down(step).await
+    }
+
+    /// Change the Volume by `delta_db`, rounded to 0.5 dB and sent as the fewest relative Steps
+    pub async fn change_volume(&self, delta_db: f32) -> Result<()> {
+        self.main_zone().change_volume(delta_db).await
+    }
+
+    /// Gradually change the Volume to `target` over `duration`
+    ///
+    /// Stops early when `cancel` is cancelled and returns the Volume which was set last.
+    pub async fn fade_volume(&self, target: Volume, duration: Duration, cancel: &CancellationToken) -> Result<Volume> {
+        self.main_zone().fade_volume(target, duration, cancel).await
+    }
+
+    /// Select a Input for the Main_Zone, use `zone` to select the Input of other Zones.
+    ///
+    /// Fails with `Error::InvalidValue` when the Input is not available
+    pub async fn select_input(&self, input: &InputId) -> Result<()> {
+        self.main_zone().select_input(input).await
+    }
+
+    /// Select a Input for the Main_Zone by its Display Name
+    ///
+    /// See `AsyncZoneControl::select_input_by_name`
+    pub async fn select_input_by_name(&self, name: &str) -> Result<InputId> {
+        self.main_zone().select_input_by_name(name).await
+    }
+
+    /// Whether HDMI Signals are passed through while the Receiver is in Standby, None when not reported
+    pub async fn get_hdmi_standby_through(&self) -> Result<Option<bool>> {
+        let info = self.get_basic_info().await?;
+        Ok(info.sound_video.and_then(|sound_video| sound_video.hdmi_standby_through))
+    }
+
+    /// Pass HDMI Signals through while the Receiver is in Standby, this is a System wide Setting
+    pub async fn set_hdmi_standby_through(&self, value: bool) -> Result<()> {
+        self.exec(hdmi_standby_through_command(value)).await?;
+        Ok(())
+    }
+
+    /// Change the Title of a Input as shown by the Receiver and listed in `get_inputs`
+    ///
+    /// Fails with `Error::InvalidArgument` when the Title is too long or contains non ASCII Characters,
+    /// or when the Input is no valid Tag, e.g. `InputId::Other("a b")`
+    pub async fn rename_input(&self, input: &InputId, title: &str) -> Result<()> {
+        let title = input::input_title(title)?;
+        self.exec(Command::put(&["System", "Input_Output", "Assign", "Name", &input.config_tag()], title)).await?;
+        Ok(())
+    }
+
+    pub async fn get_inputs(&self) -> Result<Vec<system_config::Input>> {
+        let config = self.get_system_config().await?;
+        Ok(config.inputs)
+    }
+
+    pub async fn get_zones(&self) -> Result<Vec<String>> {
+        let config = self.get_system_config().await?;
+        Ok(config.available_zones)
+    }
+
+    /// Control the FM/AM Tuner
+    ///
+    /// Fails with `Error::Unsupported` when the Receiver has no Tuner
+    pub async fn tuner(&self) -> Result<AsyncTuner<'_>> {
+        self.require(Feature::Tuner).await?;
+        Ok(AsyncTuner::new(self))
+    }
+
+    /// Fails with `Error::Unsupported` when the Receiver does not have the Feature
+    pub(crate) async fn require(&self, feature: Feature) -> Result<()> {
+        if self.supports(&feature).await? {
+            Ok(())
+        }else {
+            Err(Error::Unsupported(feature.tag().to_owned()))
+        }
+    }
+
+    /// All Features reported in the System Config
+    pub async fn get_features(&self) -> Result<Features> {
+        let config = self.get_system_config().await?;
+        Ok(config.features)
+    }
+
+    /// Whether the Receiver has the given Feature, e.g. `Feature::Tuner`
+    pub async fn supports(&self, feature: &Feature) -> Result<bool> {
+        let features = self.get_features().await?;
+        Ok(features.has(feature))
+    }
+
+    pub async fn get_system_config(&self) -> Result<system_config::SystemConfig> {
+        let res = self.exec(Command::get(&["System", "Config"])).await?;
+        system_config::parse_system_config(res)
+    }
+}
+
+fn hdmi_standby_through_command(value: bool) -> Command {
+    Command::put(&["System", "Sound_Video", "HDMI", "Standby_Through_Info"], Value::on_off(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    const BASIC_STATUS: &str = "<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Basic_Status><Power_Control><Power>On</Power></Power_Control><Volume><Lvl><Val>-300</Val><Exp>1</Exp><Unit>dB</Unit></Lvl><Mute>Off</Mute></Volume><Input><Input_Sel>HDMI1</Input_Sel></Input></Basic_Status></Main_Zone></YAMAHA_AV>";
+
+    const SYSTEM_CONFIG: &str = "<YAMAHA_AV rsp=\"GET\" RC=\"0\"><System><Config><Model_Name>RX-V473</Model_Name><Feature_Existence><Main_Zone>1</Main_Zone><Zone_2>0</Zone_2><Zone_3>0</Zone_3><Zone_4>0</Zone_4></Feature_Existence><Name><Input><HDMI_1>  Chrome </HDMI_1></Input></Name></Config></System></YAMAHA_AV>";
+
+    /// Answers every request with `body` and counts the accepted connections
+    fn serve(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ip = listener.local_addr().unwrap().to_string();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let counter = connections.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                counter.fetch_add(1, Ordering::SeqCst);
+                handle_connection(BufReader::new(stream.unwrap()), body);
+            }
+        });
+        (ip, connections)
+    }
+
+    fn handle_connection(mut stream: BufReader<TcpStream>, body: &str) {
+        loop {
+            let mut content_length = 0;
+            let mut line = String::new();
+            loop {
+                line.clear();
+                if stream.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut request = vec![0; content_length];
+            stream.read_exact(&mut request).unwrap();
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+            stream.get_mut().write_all(response.as_bytes()).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn commands_should_reuse_connection() {
+        let (ip, connections) = serve(BASIC_STATUS);
+        let avr = AsyncYamahaAvr::new(ip);
+        assert!(avr.get_power().await.unwrap());
+        assert_eq!(avr.get_volume().await.unwrap(), Volume::from_db(-30.0));
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rename_input_should_reject_invalid_tags() {
+        let (ip, connections) = serve(BASIC_STATUS);
+        let avr = AsyncYamahaAvr::new(ip);
+        match avr.rename_input(&InputId::Other("a b".to_owned()), "Kodi").await {
+            Err(Error::InvalidArgument(_)) => {},
+            other => panic!("Expected InvalidArgument, got {:?}", other)
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn hdmi_standby_through_should_use_status_tag() {
+        assert_eq!(hdmi_standby_through_command(true).to_xml(), "<YAMAHA_AV cmd=\"PUT\"><System><Sound_Video><HDMI><Standby_Through_Info>On</Standby_Through_Info></HDMI></Sound_Video></System></YAMAHA_AV>");
+    }
+
+    #[tokio::test]
+    async fn zone_should_reject_unavailable_zone() {
+        let (ip, _) = serve(SYSTEM_CONFIG);
+        let avr = AsyncYamahaAvr::new(ip);
+        assert!(avr.zone(Zone::Main).await.is_ok());
+        match avr.zone(Zone::Zone2).await {
+            Err(Error::ZoneNotAvailable(Zone::Zone2)) => {},
+            Err(other) => panic!("Expected ZoneNotAvailable, got {:?}", other),
+            Ok(_) => panic!("Expected ZoneNotAvailable")
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use crate::error::{Error, Result};
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub(crate) enum Method {
+    Get,
+    Put
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Method::Get => f.write_str("GET"),
+            Method::Put => f.write_str("PUT")
+        }
+    }
+}
+
+/// The Content of the innermost Element of a Command
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub(crate) enum Value {
+    /// Requests the current Values of the Path, only valid for GET
+    GetParam,
+    /// Plain Text, escaped when building the Command
+    Text(String),
+    /// A `Val`/`Exp`/`Unit` Triple, relative Steps like `Up 2 dB` leave `Exp` and `Unit` empty
+    Level {
+        val: String,
+        exp: Option<u32>,
+        unit: Option<String>
+    }
+}
+
+impl Value {
+    pub fn on_off(value: bool) -> Value {
+        Value::Text(if value { "On" } else { "Off" }.to_owned())
+    }
+
+    fn write(&self, xml: &mut String) {
+        match *self {
+            Value::GetParam => xml.push_str("GetParam"),
+            Value::Text(ref text) => xml.push_str(&escape(text)),
+            Value::Level { ref val, exp, ref unit } => {
+                write_element(xml, "Val", &escape(val));
+                write_element(xml, "Exp", &exp.map(|exp| exp.to_string()).unwrap_or_default());
+                write_element(xml, "Unit", &unit.as_ref().map(|unit| escape(unit)).unwrap_or_default());
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Value {
+    fn from(text: &'a str) -> Value {
+        Value::Text(text.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Value {
+        Value::Text(text)
+    }
+}
+
+/// A single Request to the Receiver, e.g. `PUT [Main_Zone, Volume, Mute] = On`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub(crate) struct Command {
+    method: Method,
+    path: Vec<String>,
+    value: Value
+}
+
+impl Command {
+    /// Query all Values below `path`
+    pub fn get(path: &[&str]) -> Command {
+        Command::new(Method::Get, path, Value::GetParam)
+    }
+
+    pub fn put<V: Into<Value>>(path: &[&str], value: V) -> Command {
+        Command::new(Method::Put, path, value.into())
+    }
+
+    fn new(method: Method, path: &[&str], value: Value) -> Command {
+        debug_assert!(!path.is_empty(), "Commands require a path");
+        Command {
+            method,
+            path: path.iter().map(|element| (*element).to_owned()).collect(),
+            value
+        }
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// Fails with `Error::InvalidArgument` when a Path Element is not a valid Tag, e.g. `a b` of a unknown Input
+    pub fn check_path(&self) -> Result<()> {
+        match self.path.iter().find(|element| !is_tag(element)) {
+            Some(element) => Err(Error::InvalidArgument(format!("Invalid element {}", element))),
+            None => Ok(())
+        }
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut xml = format!("<YAMAHA_AV cmd=\"{}\">", self.method);
+        for element in &self.path {
+            xml.push('<');
+            xml.push_str(element);
+            xml.push('>');
+        }
+        self.value.write(&mut xml);
+        for element in self.path.iter().rev() {
+            xml.push_str("</");
+            xml.push_str(element);
+            xml.push('>');
+        }
+        xml.push_str("</YAMAHA_AV>");
+        xml
+    }
+}
+
+/// Tags start with a Letter or `_`, followed by Letters, Digits, `_`, `-` or `.`
+fn is_tag(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')),
+        _ => false
+    }
+}
+
+fn write_element(xml: &mut String, name: &str, content: &str) {
+    xml.push_str(&format!("<{name}>{}</{name}>", content, name = name));
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_should_request_params() {
+        let cmd = Command::get(&["System", "Config"]);
+        assert_eq!(cmd.to_xml(), "<YAMAHA_AV cmd=\"GET\"><System><Config>GetParam</Config></System></YAMAHA_AV>");
+    }
+
+    #[test]
+    fn put_should_escape_text() {
+        let cmd = Command::put(&["Main_Zone", "Input", "Input_Sel"], "R&B <Live>");
+        assert_eq!(cmd.to_xml(), "<YAMAHA_AV cmd=\"PUT\"><Main_Zone><Input><Input_Sel>R&amp;B &lt;Live&gt;</Input_Sel></Input></Main_Zone></YAMAHA_AV>");
+    }
+
+    #[test]
+    fn check_path_should_reject_invalid_tags() {
+        assert!(Command::put(&["System", "Input_Output", "Assign", "Name", "HDMI_1"], "Kodi").check_path().is_ok());
+        assert!(Command::get(&["Main_Zone", "Sound_Video", "HDMI", "Output", "OUT_1"]).check_path().is_ok());
+        for element in &["a b", "", "1st", "<HDMI_1>", "Name/x"] {
+            assert!(Command::put(&["System", "Input_Output", "Assign", "Name", element], "Kodi").check_path().is_err());
+        }
+    }
+
+    #[test]
+    fn put_should_nest_levels() {
+        let cmd = Command::put(&["Main_Zone", "Volume", "Lvl"], Value::Level { val: "Up 2 dB".to_owned(), exp: None, unit: None });
+        assert_eq!(cmd.to_xml(), "<YAMAHA_AV cmd=\"PUT\"><Main_Zone><Volume><Lvl><Val>Up 2 dB</Val><Exp></Exp><Unit></Unit></Lvl></Volume></Main_Zone></YAMAHA_AV>");
+    }
+}
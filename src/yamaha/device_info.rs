@@ -0,0 +1,82 @@
+use crate::error::{Error, Result};
+use super::element::Element;
+
+const REMOTE_CONTROL_SERVICE: &str = "urn:schemas-yamaha-com:service:X_YamahaRemoteControl:1";
+
+/// The UPnP Device Description of a Receiver
+#[derive(PartialEq, Debug, Clone)]
+pub struct DeviceInfo {
+    pub friendly_name: String,
+    pub manufacturer: String,
+    pub model_name: String,
+    pub serial_number: Option<String>,
+    /// The Unique Device Name, e.g. `uuid:5f9ec1b3-ed59-1900-4530-00a0dea0d4e6`
+    pub udn: String,
+    pub presentation_url: Option<String>,
+    /// Base URL for the Yamaha Remote Control URLs
+    pub url_base: Option<String>,
+    /// Path of the Yamaha Remote Control endpoint, usually `/YamahaRemoteControl/ctrl`
+    pub control_url: Option<String>,
+    /// Path of the Unit Description, usually `/YamahaRemoteControl/desc.xml`
+    pub spec_url: Option<String>
+}
+
+impl DeviceInfo {
+    /// Whether this Device is a Yamaha RX or HTR Receiver
+    pub fn is_receiver(&self) -> bool {
+        self.manufacturer.contains("Yamaha") && (self.model_name.starts_with("RX-") || self.model_name.starts_with("HTR-"))
+    }
+}
+
+pub fn parse_device_info(xml: String) -> Result<DeviceInfo> {
+    let root = Element::parse(&xml)?;
+    let device = root.child("device")
+        .ok_or_else(|| Error::UnexpectedResponse("Missing device in device description".to_owned()))?;
+    let text = |element: &Element, path: &str| element.find_text(path).map(|value| value.trim().to_owned());
+    let remote_control = root.find("X_device/X_serviceList").map(Element::children).unwrap_or_default()
+        .iter()
+        .find(|service| service.find_text("X_specType").map(str::trim) == Some(REMOTE_CONTROL_SERVICE));
+
+    Ok(DeviceInfo {
+        friendly_name: required(text(device, "friendlyName"), "friendlyName")?,
+        manufacturer: required(text(device, "manufacturer"), "manufacturer")?,
+        model_name: required(text(device, "modelName"), "modelName")?,
+        serial_number: text(device, "serialNumber"),
+        udn: required(text(device, "UDN"), "UDN")?,
+        presentation_url: text(device, "presentationURL"),
+        url_base: text(&root, "X_device/X_URLBase"),
+        control_url: remote_control.and_then(|service| text(service, "X_controlURL")),
+        spec_url: remote_control.and_then(|service| text(service, "X_unitDescURL"))
+    })
+}
+
+fn required(value: Option<String>, element: &str) -> Result<String> {
+    value.ok_or_else(|| Error::UnexpectedResponse(format!("Missing {} in device description", element)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_device_info_should_parse_xml() {
+        let input = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?><root xmlns=\"urn:schemas-upnp-org:device-1-0\" xmlns:yamaha=\"urn:schemas-yamaha-com:device-1-0\"><specVersion><major>1</major><minor>0</minor></specVersion><device><deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType><friendlyName>RX-V473 05852093</friendlyName><manufacturer>Yamaha Corporation</manufacturer><manufacturerURL>http://www.yamaha.com/</manufacturerURL><modelDescription>AV Receiver</modelDescription><modelName>RX-V473</modelName><modelNumber>V473</modelNumber><serialNumber>05852093</serialNumber><UDN>uuid:5f9ec1b3-ed59-1900-4530-00a0dea0d4e6</UDN><presentationURL>http://192.168.2.102/</presentationURL><serviceList><service><serviceType>urn:schemas-upnp-org:service:AVTransport:1</serviceType><controlURL>/AVTransport/ctrl</controlURL></service></serviceList></device><yamaha:X_device><yamaha:X_URLBase>http://192.168.2.102:80/</yamaha:X_URLBase><yamaha:X_serviceList><yamaha:X_service><yamaha:X_specType>urn:schemas-yamaha-com:service:X_YamahaRemoteControl:1</yamaha:X_specType><yamaha:X_controlURL>/YamahaRemoteControl/ctrl</yamaha:X_controlURL><yamaha:X_unitDescURL>/YamahaRemoteControl/desc.xml</yamaha:X_unitDescURL></yamaha:X_service></yamaha:X_serviceList></yamaha:X_device></root>");
+        assert_eq!(parse_device_info(input).unwrap(), DeviceInfo {
+            friendly_name: String::from("RX-V473 05852093"),
+            manufacturer: String::from("Yamaha Corporation"),
+            model_name: String::from("RX-V473"),
+            serial_number: Some(String::from("05852093")),
+            udn: String::from("uuid:5f9ec1b3-ed59-1900-4530-00a0dea0d4e6"),
+            presentation_url: Some(String::from("http://192.168.2.102/")),
+            url_base: Some(String::from("http://192.168.2.102:80/")),
+            control_url: Some(String::from("/YamahaRemoteControl/ctrl")),
+            spec_url: Some(String::from("/YamahaRemoteControl/desc.xml"))
+        });
+    }
+
+    #[test]
+    fn parse_device_info_should_fail_without_model_name() {
+        let input = String::from("<root><device><friendlyName>TV</friendlyName><manufacturer>Other</manufacturer><UDN>uuid:1</UDN></device></root>");
+        assert!(parse_device_info(input).is_err());
+    }
+}
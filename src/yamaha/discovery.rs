@@ -0,0 +1,221 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+use hyper::Uri;
+use tokio::net::UdpSocket;
+use tokio::runtime::Builder;
+use tokio::time::{self, Instant};
+
+use crate::error::Result;
+use super::{AsyncYamahaAvr, YamahaAvr};
+use super::http::HttpClient;
+use super::device_info::{DeviceInfo, parse_device_info};
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:MediaRenderer:1";
+
+/// Search the local network for Yamaha AVRs.
+///
+/// Sends a SSDP M-SEARCH to the multicast group and collects all responses received within `timeout`.
+/// The Device Descriptions of the Responders are fetched concurrently afterwards, each within `timeout` as well.
+pub async fn discover(timeout: Duration) -> Result<Vec<AsyncYamahaAvr>> {
+    let addr: SocketAddr = SSDP_ADDR.parse().unwrap();
+    search(addr, timeout).await
+}
+
+/// Blocking variant of `discover`
+pub fn discover_blocking(timeout: Duration) -> Result<Vec<YamahaAvr>> {
+    let runtime = Builder::new_current_thread().enable_all().build()?;
+    let receivers = runtime.block_on(discover(timeout))?;
+    receivers.into_iter()
+        .map(YamahaAvr::from_async)
+        .collect()
+}
+
+async fn search(target: SocketAddr, timeout: Duration) -> Result<Vec<AsyncYamahaAvr>> {
+    let bind_addr = if target.ip().is_loopback() { "127.0.0.1:0" } else { "0.0.0.0:0" };
+    let socket = UdpSocket::bind(bind_addr).await?;
+    let request = format!("M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: {}\r\nST: {}\r\n\r\n", SSDP_ADDR, timeout.as_secs().max(1), SEARCH_TARGET);
+    socket.send_to(request.as_bytes(), target).await?;
+
+    let deadline = Instant::now() + timeout;
+    let mut locations: Vec<String> = Vec::new();
+    let mut buf = [0; 2048];
+    while let Ok(received) = time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        // A single failed Receive should not discard the Responses collected so far
+        let len = match received {
+            Ok((len, _)) => len,
+            Err(_) => continue
+        };
+        let response = String::from_utf8_lossy(&buf[..len]);
+        if let Some(location) = parse_location(&response) {
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+    }
+
+    let client = HttpClient::new();
+    let probes: Vec<_> = locations.into_iter()
+        .map(|location| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                time::timeout(timeout, probe(&client, &location)).await.ok().flatten()
+            })
+        })
+        .collect();
+    let mut receivers = Vec::new();
+    for probe in probes {
+        if let Ok(Some(receiver)) = probe.await {
+            receivers.push(receiver);
+        }
+    }
+    Ok(receivers)
+}
+
+/// Fetch the device description and build a `AsyncYamahaAvr` when it belongs to a Yamaha receiver
+async fn probe(client: &HttpClient, location: &str) -> Option<AsyncYamahaAvr> {
+    let uri: Uri = location.parse().ok()?;
+    let ip = uri.host()?.to_owned();
+    let xml = client.get(location).await.ok()?;
+    let device_info = parse_device_info(xml).ok()?;
+    if !device_info.is_receiver() {
+        return None;
+    }
+    let control_url = resolve_url(&uri, &device_info, device_info.control_url.as_ref()?);
+    let spec_url = device_info.spec_url.as_ref().map(String::as_ref).unwrap_or("/YamahaRemoteControl/desc.xml");
+    let desc_url = resolve_url(&uri, &device_info, spec_url);
+    Some(AsyncYamahaAvr::with_device_info(ip, control_url, desc_url, device_info))
+}
+
+fn parse_location(response: &str) -> Option<String> {
+    response.lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if name.trim().eq_ignore_ascii_case("location") {
+                Some(value.trim().to_owned())
+            }else {
+                None
+            }
+        })
+        .next()
+}
+
+/// Resolve a Path of the Device Description against `X_URLBase`, falling back to the Location
+fn resolve_url(location: &Uri, device_info: &DeviceInfo, path: &str) -> String {
+    let base = match device_info.url_base {
+        Some(ref base) => base.clone(),
+        None => match (location.host(), location.port()) {
+            (Some(host), Some(port)) => format!("http://{}:{}", host, port),
+            (Some(host), None) => format!("http://{}", host),
+            (None, _) => return path.to_owned()
+        }
+    };
+    join_url(&base, path)
+}
+
+fn join_url(base: &str, path: &str) -> String {
+    if path.starts_with("http://") {
+        return path.to_owned();
+    }
+    format!("{}/{}", base.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, UdpSocket};
+    use std::thread;
+
+    const DESCRIPTION: &str = "<?xml version=\"1.0\" encoding=\"utf-8\"?><root xmlns=\"urn:schemas-upnp-org:device-1-0\" xmlns:yamaha=\"urn:schemas-yamaha-com:device-1-0\"><specVersion><major>1</major><minor>0</minor></specVersion><device><deviceType>urn:schemas-upnp-org:device:MediaRenderer:1</deviceType><friendlyName>RX-V473 05852093</friendlyName><manufacturer>Yamaha Corporation</manufacturer><manufacturerURL>http://www.yamaha.com/</manufacturerURL><modelDescription>AV Receiver</modelDescription><modelName>RX-V473</modelName><modelNumber>V473</modelNumber><serialNumber>05852093</serialNumber><UDN>uuid:5f9ec1b3-ed59-1900-4530-00a0dea0d4e6</UDN><presentationURL>http://127.0.0.1/</presentationURL></device><yamaha:X_device><yamaha:X_URLBase>http://127.0.0.1:80/</yamaha:X_URLBase><yamaha:X_serviceList><yamaha:X_service><yamaha:X_specType>urn:schemas-yamaha-com:service:X_YamahaRemoteControl:1</yamaha:X_specType><yamaha:X_controlURL>/YamahaRemoteControl/ctrl</yamaha:X_controlURL><yamaha:X_unitDescURL>/YamahaRemoteControl/desc.xml</yamaha:X_unitDescURL></yamaha:X_service></yamaha:X_serviceList></yamaha:X_device></root>";
+
+    fn serve_description(body: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf);
+                let response = format!("HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        port
+    }
+
+    /// Accept Connections without ever answering them
+    fn serve_silent() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let mut streams = Vec::new();
+            for stream in listener.incoming() {
+                streams.push(stream);
+            }
+        });
+        port
+    }
+
+    fn serve_ssdp(locations: Vec<String>) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = socket.local_addr().unwrap();
+        thread::spawn(move || {
+            let mut buf = [0; 1024];
+            let (len, sender) = socket.recv_from(&mut buf).unwrap();
+            let request = String::from_utf8_lossy(&buf[..len]);
+            assert!(request.starts_with("M-SEARCH * HTTP/1.1"));
+            for location in locations {
+                let response = format!("HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=1800\r\nLOCATION: {}\r\nST: {}\r\n\r\n", location, SEARCH_TARGET);
+                socket.send_to(response.as_bytes(), sender).unwrap();
+            }
+        });
+        addr
+    }
+
+    #[test]
+    fn parse_location_should_read_header_case_insensitive() {
+        let response = "HTTP/1.1 200 OK\r\nLocation: http://192.168.2.102:49154/MediaRenderer/desc.xml\r\n\r\n";
+        assert_eq!(parse_location(response), Some(String::from("http://192.168.2.102:49154/MediaRenderer/desc.xml")));
+    }
+
+    #[test]
+    fn resolve_url_should_fallback_to_location() {
+        let mut device_info = parse_device_info(String::from(DESCRIPTION)).unwrap();
+        device_info.url_base = None;
+        let location: Uri = "http://192.168.2.102:49154/MediaRenderer/desc.xml".parse().unwrap();
+        assert_eq!(resolve_url(&location, &device_info, "/YamahaRemoteControl/ctrl"), "http://192.168.2.102:49154/YamahaRemoteControl/ctrl");
+    }
+
+    #[tokio::test]
+    async fn search_should_find_receivers() {
+        let port = serve_description(DESCRIPTION);
+        let location = format!("http://127.0.0.1:{}/desc.xml", port);
+        let ssdp = serve_ssdp(vec![location.clone(), location]);
+        let receivers = search(ssdp, Duration::from_millis(500)).await.unwrap();
+        assert_eq!(receivers.len(), 1);
+        assert_eq!(receivers[0].ip(), "127.0.0.1");
+        assert_eq!(receivers[0].model_name(), Some("RX-V473"));
+        assert_eq!(receivers[0].control_url(), "http://127.0.0.1:80/YamahaRemoteControl/ctrl");
+        assert_eq!(receivers[0].desc_url(), "http://127.0.0.1:80/YamahaRemoteControl/desc.xml");
+    }
+
+    #[tokio::test]
+    async fn search_should_not_wait_for_silent_devices() {
+        let silent = format!("http://127.0.0.1:{}/desc.xml", serve_silent());
+        let location = format!("http://127.0.0.1:{}/desc.xml", serve_description(DESCRIPTION));
+        let ssdp = serve_ssdp(vec![silent, location]);
+        let started = Instant::now();
+        let receivers = search(ssdp, Duration::from_millis(500)).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(receivers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn search_should_skip_other_devices() {
+        let port = serve_description("<root><device><friendlyName>TV</friendlyName><manufacturer>Other</manufacturer><modelName>TV</modelName><UDN>uuid:1</UDN></device></root>");
+        let ssdp = serve_ssdp(vec![format!("http://127.0.0.1:{}/desc.xml", port)]);
+        let receivers = search(ssdp, Duration::from_millis(500)).await.unwrap();
+        assert!(receivers.is_empty());
+    }
+}
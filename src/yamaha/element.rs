@@ -0,0 +1,141 @@
+use std::str::FromStr;
+use xml::reader::XmlEvent;
+use crate::error::{Error, Result};
+
+/// A lightweight XML Element Tree of a Response
+///
+/// Queried by Paths relative to an Element, e.g. `root.find("Main_Zone/Basic_Status/Volume/Lvl/Val")`.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub(crate) struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>
+}
+
+impl Element {
+    /// Parse a complete Document and return its Root Element
+    pub fn parse(xml: &str) -> Result<Element> {
+        let reader = xml::reader::EventReader::from_str(xml);
+        let mut stack: Vec<Element> = Vec::new();
+        for event in reader {
+            match event {
+                Ok(XmlEvent::StartElement { name, attributes, .. }) => {
+                    stack.push(Element {
+                        name: name.local_name,
+                        attributes: attributes.into_iter()
+                            .map(|attribute| (attribute.name.local_name, attribute.value))
+                            .collect(),
+                        ..Element::default()
+                    });
+                }
+                Ok(XmlEvent::EndElement { .. }) => {
+                    let element = stack.pop()
+                        .ok_or_else(|| Error::UnexpectedResponse("Unbalanced element".to_owned()))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element)
+                    }
+                }
+                Ok(XmlEvent::Characters(s)) | Ok(XmlEvent::CData(s)) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&s);
+                    }
+                }
+                Err(e) => return Err(Error::from(e)),
+                _ => {}
+            }
+        }
+        Err(Error::UnexpectedResponse("Empty response".to_owned()))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_ref())
+    }
+
+    /// The Text Content as sent by the Receiver, including padding
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn children(&self) -> &[Element] {
+        &self.children
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// The first Element matching a `/` separated Path relative to this Element
+    pub fn find(&self, path: &str) -> Option<&Element> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(self, |element, name| element.child(name))
+    }
+
+    /// The Text of the Element at `path`
+    pub fn find_text(&self, path: &str) -> Option<&str> {
+        self.find(path).map(Element::text)
+    }
+
+    /// Parse the trimmed Text, None when it is empty
+    pub fn parse_text<T: FromStr>(&self) -> Result<Option<T>> {
+        let text = self.text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        text.parse()
+            .map(Some)
+            .map_err(|_| Error::UnexpectedResponse(format!("Invalid value {} in {}", text, self.name)))
+    }
+
+    /// Parse the Text of the Element at `path`, None when it is missing or empty
+    pub fn find_parsed<T: FromStr>(&self, path: &str) -> Result<Option<T>> {
+        match self.find(path) {
+            Some(element) => element.parse_text(),
+            None => Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_build_tree() {
+        let root = Element::parse("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Volume><Lvl><Val>-300</Val></Lvl><Mute>Off</Mute></Volume></Main_Zone></YAMAHA_AV>").unwrap();
+        assert_eq!(root.name(), "YAMAHA_AV");
+        assert_eq!(root.attribute("RC"), Some("0"));
+        assert_eq!(root.find_text("Main_Zone/Volume/Lvl/Val"), Some("-300"));
+        assert_eq!(root.find_text("Main_Zone/Volume/Mute"), Some("Off"));
+        assert!(root.find("Main_Zone/Input").is_none());
+        assert_eq!(root.find("Main_Zone/Volume").unwrap().children().len(), 2);
+    }
+
+    #[test]
+    fn parse_should_keep_padding() {
+        let root = Element::parse("<Name><Title>  Chrome </Title></Name>").unwrap();
+        assert_eq!(root.find_text("Title"), Some("  Chrome "));
+    }
+
+    #[test]
+    fn find_parsed_should_skip_empty_text() {
+        let root = Element::parse("<Info><Src_Number>1</Src_Number><Src_Name></Src_Name><Exp>x</Exp></Info>").unwrap();
+        assert_eq!(root.find_parsed::<u32>("Src_Number").unwrap(), Some(1));
+        assert_eq!(root.find_parsed::<u32>("Src_Name").unwrap(), None);
+        assert_eq!(root.find_parsed::<u32>("Missing").unwrap(), None);
+        assert!(root.find_parsed::<u32>("Exp").is_err());
+    }
+
+    #[test]
+    fn parse_should_fail_on_invalid_xml() {
+        assert!(Element::parse("<YAMAHA_AV><Main_Zone></YAMAHA_AV>").is_err());
+    }
+}
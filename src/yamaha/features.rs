@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// A Function listed in `System/Config/Feature_Existence`
+///
+/// Tags which are not known to this crate are kept as `Unknown`.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub enum Feature {
+    MainZone,
+    Zone2,
+    Zone3,
+    Zone4,
+    ZoneB,
+    Tuner,
+    Dab,
+    HdRadio,
+    Rhapsody,
+    Napster,
+    SiriusIr,
+    SiriusInternetRadio,
+    SiriusXm,
+    Pandora,
+    Spotify,
+    Server,
+    NetRadio,
+    Usb,
+    IpodUsb,
+    AirPlay,
+    Bluetooth,
+    MusicCastLink,
+    Unknown(String)
+}
+
+impl Feature {
+    /// The Tag used by the Receiver, e.g. `NET_RADIO`
+    pub fn tag(&self) -> &str {
+        match *self {
+            Feature::MainZone => "Main_Zone",
+            Feature::Zone2 => "Zone_2",
+            Feature::Zone3 => "Zone_3",
+            Feature::Zone4 => "Zone_4",
+            Feature::ZoneB => "Zone_B",
+            Feature::Tuner => "Tuner",
+            Feature::Dab => "DAB",
+            Feature::HdRadio => "HD_Radio",
+            Feature::Rhapsody => "Rhapsody",
+            Feature::Napster => "Napster",
+            Feature::SiriusIr => "SIRIUS_IR",
+            Feature::SiriusInternetRadio => "SIRIUS_InternetRadio",
+            Feature::SiriusXm => "SiriusXM",
+            Feature::Pandora => "Pandora",
+            Feature::Spotify => "Spotify",
+            Feature::Server => "SERVER",
+            Feature::NetRadio => "NET_RADIO",
+            Feature::Usb => "USB",
+            Feature::IpodUsb => "iPod_USB",
+            Feature::AirPlay => "AirPlay",
+            Feature::Bluetooth => "Bluetooth",
+            Feature::MusicCastLink => "MusicCast_Link",
+            Feature::Unknown(ref tag) => tag
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Feature {
+        match tag {
+            "Main_Zone" => Feature::MainZone,
+            "Zone_2" => Feature::Zone2,
+            "Zone_3" => Feature::Zone3,
+            "Zone_4" => Feature::Zone4,
+            "Zone_B" => Feature::ZoneB,
+            "Tuner" => Feature::Tuner,
+            "DAB" => Feature::Dab,
+            "HD_Radio" => Feature::HdRadio,
+            "Rhapsody" => Feature::Rhapsody,
+            "Napster" => Feature::Napster,
+            "SIRIUS_IR" => Feature::SiriusIr,
+            "SIRIUS_InternetRadio" => Feature::SiriusInternetRadio,
+            "SiriusXM" => Feature::SiriusXm,
+            "Pandora" => Feature::Pandora,
+            "Spotify" => Feature::Spotify,
+            "SERVER" => Feature::Server,
+            "NET_RADIO" => Feature::NetRadio,
+            "USB" => Feature::Usb,
+            "iPod_USB" => Feature::IpodUsb,
+            "AirPlay" => Feature::AirPlay,
+            "Bluetooth" => Feature::Bluetooth,
+            "MusicCast_Link" => Feature::MusicCastLink,
+            tag => Feature::Unknown(tag.to_owned())
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+/// All Features reported by the Receiver and whether they exist
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Features {
+    map: HashMap<Feature, bool>
+}
+
+impl Features {
+    pub(crate) fn insert(&mut self, feature: Feature, exists: bool) {
+        self.map.insert(feature, exists);
+    }
+
+    /// False for Features which are missing or reported as not existing
+    pub fn has(&self, feature: &Feature) -> bool {
+        self.map.get(feature).cloned().unwrap_or(false)
+    }
+
+    /// The existing Features in no particular Order
+    pub fn available(&self) -> Vec<&Feature> {
+        self.map.iter()
+            .filter(|(_, exists)| **exists)
+            .map(|(feature, _)| feature)
+            .collect()
+    }
+
+    pub fn as_map(&self) -> &HashMap<Feature, bool> {
+        &self.map
+    }
+
+    pub fn tuner(&self) -> bool {
+        self.has(&Feature::Tuner)
+    }
+
+    pub fn dab(&self) -> bool {
+        self.has(&Feature::Dab)
+    }
+
+    pub fn hd_radio(&self) -> bool {
+        self.has(&Feature::HdRadio)
+    }
+
+    pub fn rhapsody(&self) -> bool {
+        self.has(&Feature::Rhapsody)
+    }
+
+    pub fn sirius_ir(&self) -> bool {
+        self.has(&Feature::SiriusIr)
+    }
+
+    pub fn pandora(&self) -> bool {
+        self.has(&Feature::Pandora)
+    }
+
+    pub fn server(&self) -> bool {
+        self.has(&Feature::Server)
+    }
+
+    pub fn net_radio(&self) -> bool {
+        self.has(&Feature::NetRadio)
+    }
+
+    pub fn usb(&self) -> bool {
+        self.has(&Feature::Usb)
+    }
+
+    pub fn ipod(&self) -> bool {
+        self.has(&Feature::IpodUsb)
+    }
+
+    pub fn air_play(&self) -> bool {
+        self.has(&Feature::AirPlay)
+    }
+
+    pub fn bluetooth(&self) -> bool {
+        self.has(&Feature::Bluetooth)
+    }
+
+    pub fn spotify(&self) -> bool {
+        self.has(&Feature::Spotify)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feature_should_keep_unknown_tags() {
+        assert_eq!(Feature::from_tag("NET_RADIO"), Feature::NetRadio);
+        assert_eq!(Feature::from_tag("Napster"), Feature::Napster);
+        let unknown = Feature::from_tag("Deezer");
+        assert_eq!(unknown, Feature::Unknown("Deezer".to_owned()));
+        assert_eq!(unknown.tag(), "Deezer");
+    }
+}
@@ -0,0 +1,133 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter;
+use std::str::FromStr;
+use crate::error::Error;
+
+/// A single Version Number like `1.14`
+///
+/// The minor Version is a decimal Fraction as reported, so `1.9` is newer than `1.14`.
+/// Two Versions are only equal when they are reported the same way, `1.5` sorts directly before `1.50`.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub struct VersionNumber {
+    pub major: u32,
+    pub minor: u32,
+    /// The Digits of the minor Version as reported, e.g. 2 for `1.04` and 1 for `1.9`
+    pub minor_digits: usize
+}
+
+impl VersionNumber {
+    /// The minor Version with its leading Zeros, e.g. `04`
+    fn fraction(&self) -> String {
+        format!("{:0width$}", self.minor, width = self.minor_digits)
+    }
+}
+
+impl PartialOrd for VersionNumber {
+    fn partial_cmp(&self, other: &VersionNumber) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VersionNumber {
+    fn cmp(&self, other: &VersionNumber) -> Ordering {
+        let (fraction, other_fraction) = (self.fraction(), other.fraction());
+        let digits = fraction.len().max(other_fraction.len());
+        let padded = |fraction: &str| fraction.chars().chain(iter::repeat('0')).take(digits).collect::<String>();
+        self.major.cmp(&other.major)
+            .then_with(|| padded(&fraction).cmp(&padded(&other_fraction)))
+            .then_with(|| fraction.len().cmp(&other_fraction.len()))
+    }
+}
+
+impl fmt::Display for VersionNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{:0width$}", self.major, self.minor, width = self.minor_digits)
+    }
+}
+
+impl FromStr for VersionNumber {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<VersionNumber, Error> {
+        let invalid = || Error::InvalidArgument(format!("Invalid version {}", s));
+        let (major, minor) = s.trim().split_once('.').ok_or_else(invalid)?;
+        if !minor.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        Ok(VersionNumber {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+            minor_digits: minor.len()
+        })
+    }
+}
+
+/// The Firmware Version as reported in `System/Config/Version`, e.g. `1.14/1.04`
+///
+/// Versions are ordered by the main Firmware first, then by the sub Firmware.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Hash)]
+pub struct FirmwareVersion {
+    pub main: VersionNumber,
+    /// None for Models only reporting a single Version
+    pub sub: Option<VersionNumber>
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.sub {
+            Some(sub) => write!(f, "{}/{}", self.main, sub),
+            None => write!(f, "{}", self.main)
+        }
+    }
+}
+
+impl FromStr for FirmwareVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<FirmwareVersion, Error> {
+        let (main, sub) = match s.split_once('/') {
+            Some((main, sub)) => (main, Some(sub.parse()?)),
+            None => (s, None)
+        };
+        Ok(FirmwareVersion {
+            main: main.parse()?,
+            sub
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn firmware_version_should_parse_main_and_sub() {
+        let version: FirmwareVersion = "1.14/1.04".parse().unwrap();
+        assert_eq!(version.main, VersionNumber { major: 1, minor: 14, minor_digits: 2 });
+        assert_eq!(version.sub, Some(VersionNumber { major: 1, minor: 4, minor_digits: 2 }));
+        assert_eq!(version.to_string(), "1.14/1.04");
+        assert!("1.x/1.04".parse::<FirmwareVersion>().is_err());
+        assert!("1.+4".parse::<VersionNumber>().is_err());
+    }
+
+    #[test]
+    fn firmware_version_should_compare_as_decimals() {
+        let version = |s: &str| s.parse::<VersionNumber>().unwrap();
+        assert!(version("1.14") < version("1.9"));
+        assert!(version("1.09") < version("1.14"));
+        assert!(version("1.5") < version("1.50"));
+        assert!(version("1.50") < version("1.51"));
+        assert_ne!(version("1.9"), version("1.09"));
+        assert_ne!(version("1.5"), version("1.50"));
+        assert!("1.14/1.04".parse::<FirmwareVersion>().unwrap() < "1.14/1.10".parse().unwrap());
+        assert!("1.14/2.00".parse::<FirmwareVersion>().unwrap() < "1.9/1.04".parse().unwrap());
+    }
+
+    #[test]
+    fn firmware_version_should_display_as_reported() {
+        for version in &["1.9/1.5", "1.14/1.04", "2.10", "1.050/1.0"] {
+            assert_eq!(version.parse::<FirmwareVersion>().unwrap().to_string(), *version);
+        }
+    }
+}
@@ -1,27 +1,42 @@
-use std::result;
-use std::str;
-use futures::{Future, Stream, future};
-use hyper;
-use hyper::{Client, Request, Response, Method};
-use hyper::header::{ContentLength, ContentType};
-use tokio_core::reactor::Core;
+use hyper::{Body, Client, Method, Request, Response};
+use hyper::client::HttpConnector;
+use hyper::header::CONTENT_TYPE;
+use crate::error::{Error, Result};
 
-pub fn exec(ip: String, body: String) -> result::Result<String, hyper::Error> {
-    let mut core = Core::new()?;
-    let client = Client::new(&core.handle());
-    let uri = format!("http://{}/YamahaRemoteControl/ctrl", ip).parse().unwrap();
-    let mut req: Request = Request::new(Method::Post, uri);
-    req.headers_mut().set(ContentType::xml());
-    req.headers_mut().set(ContentLength(body.len() as u64));
-    req.set_body(body);
-    let work = client.request(req).and_then(|res: Response| {
-        res.body().fold(Vec::new(), |mut v, chunk| {
-            v.extend(&chunk[..]);
-            future::ok::<_, hyper::Error>(v)
-        }).and_then(move |chunks| {
-            let body = String::from_utf8(chunks).unwrap();
-            future::ok(body)
-        })
-    });
-    core.run(work)
+/// Shared HTTP Client, keeps connections to the Receiver alive between Commands
+#[derive(Clone, Default)]
+pub struct HttpClient {
+    client: Client<HttpConnector>
+}
+
+impl HttpClient {
+    pub fn new() -> HttpClient {
+        HttpClient::default()
+    }
+
+    pub async fn exec(&self, url: &str, body: String) -> Result<String> {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(url)
+            .header(CONTENT_TYPE, "text/xml")
+            .body(Body::from(body))?;
+        let res = self.client.request(req).await?;
+        read_body(res).await
+    }
+
+    pub async fn get(&self, url: &str) -> Result<String> {
+        let uri = url.parse().map_err(hyper::http::Error::from)?;
+        let res = self.client.get(uri).await?;
+        read_body(res).await
+    }
+}
+
+async fn read_body(res: Response<Body>) -> Result<String> {
+    let status = res.status();
+    if !status.is_success() {
+        return Err(Error::Status(status));
+    }
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    let body = String::from_utf8(body.to_vec())?;
+    Ok(body)
 }
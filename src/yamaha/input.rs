@@ -0,0 +1,188 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::error::{Error, Result};
+
+/// The Receiver pads Input Titles to this Length, e.g. `  Chrome `
+pub const MAX_INPUT_TITLE_LENGTH: usize = 9;
+
+/// An Input of the Receiver
+///
+/// The Receiver uses two Spellings: Commands like `Input_Sel` expect e.g. `HDMI1`, `V-AUX` or `NET RADIO`,
+/// while `System/Config` and `System/Input_Output` use Tags like `HDMI_1`, `V_AUX` or `NET_RADIO`.
+/// Both are accepted when parsing.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub enum InputId {
+    Hdmi(u8),
+    Av(u8),
+    Audio(u8),
+    VAux,
+    Phono,
+    MultiCh,
+    Tuner,
+    Dock,
+    Usb,
+    IpodUsb,
+    NetRadio,
+    Server,
+    Bluetooth,
+    AirPlay,
+    Rhapsody,
+    Napster,
+    SiriusXm,
+    Pandora,
+    Spotify,
+    /// Inputs unknown to this crate, kept in the Spelling reported by the Receiver
+    Other(String)
+}
+
+impl InputId {
+    /// The Name used in Commands, e.g. `HDMI1`
+    pub fn command_name(&self) -> String {
+        self.spellings().0
+    }
+
+    /// The Tag used in `System/Config/Name/Input` and `System/Input_Output`, e.g. `HDMI_1`
+    pub fn config_tag(&self) -> String {
+        self.spellings().1
+    }
+
+    fn spellings(&self) -> (String, String) {
+        let (command_name, config_tag) = match *self {
+            InputId::Hdmi(number) => return (format!("HDMI{}", number), format!("HDMI_{}", number)),
+            InputId::Av(number) => return (format!("AV{}", number), format!("AV_{}", number)),
+            InputId::Audio(number) => return (format!("AUDIO{}", number), format!("AUDIO_{}", number)),
+            InputId::Other(ref name) => return (name.clone(), name.clone()),
+            InputId::VAux => ("V-AUX", "V_AUX"),
+            InputId::Phono => ("PHONO", "PHONO"),
+            InputId::MultiCh => ("MULTI CH", "MULTI_CH"),
+            InputId::Tuner => ("TUNER", "TUNER"),
+            InputId::Dock => ("DOCK", "DOCK"),
+            InputId::Usb => ("USB", "USB"),
+            InputId::IpodUsb => ("iPod_USB", "iPod_USB"),
+            InputId::NetRadio => ("NET RADIO", "NET_RADIO"),
+            InputId::Server => ("SERVER", "SERVER"),
+            InputId::Bluetooth => ("Bluetooth", "Bluetooth"),
+            InputId::AirPlay => ("AirPlay", "AirPlay"),
+            InputId::Rhapsody => ("Rhapsody", "Rhapsody"),
+            InputId::Napster => ("Napster", "Napster"),
+            InputId::SiriusXm => ("SiriusXM", "SiriusXM"),
+            InputId::Pandora => ("Pandora", "Pandora"),
+            InputId::Spotify => ("Spotify", "Spotify")
+        };
+        (command_name.to_owned(), config_tag.to_owned())
+    }
+}
+
+impl fmt::Display for InputId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.command_name())
+    }
+}
+
+/// Parses either Spelling case-insensitively, e.g. `HDMI1`, `HDMI_1` or `hdmi1`
+impl FromStr for InputId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<InputId> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err(Error::InvalidArgument("Empty input".to_owned()));
+        }
+        let normalized: String = name.chars()
+            .filter(|c| !matches!(c, ' ' | '_' | '-'))
+            .collect::<String>()
+            .to_uppercase();
+        let numbered = |prefix: &str| normalized.strip_prefix(prefix).and_then(|number| number.parse::<u8>().ok());
+        if let Some(number) = numbered("HDMI") {
+            return Ok(InputId::Hdmi(number));
+        }
+        if let Some(number) = numbered("AV") {
+            return Ok(InputId::Av(number));
+        }
+        if let Some(number) = numbered("AUDIO") {
+            return Ok(InputId::Audio(number));
+        }
+        let input = match normalized.as_str() {
+            "VAUX" => InputId::VAux,
+            "PHONO" => InputId::Phono,
+            "MULTICH" => InputId::MultiCh,
+            "TUNER" => InputId::Tuner,
+            "DOCK" => InputId::Dock,
+            "USB" => InputId::Usb,
+            "IPODUSB" => InputId::IpodUsb,
+            "NETRADIO" => InputId::NetRadio,
+            "SERVER" => InputId::Server,
+            "BLUETOOTH" => InputId::Bluetooth,
+            "AIRPLAY" => InputId::AirPlay,
+            "RHAPSODY" => InputId::Rhapsody,
+            "NAPSTER" => InputId::Napster,
+            "SIRIUSXM" => InputId::SiriusXm,
+            "PANDORA" => InputId::Pandora,
+            "SPOTIFY" => InputId::Spotify,
+            _ => InputId::Other(name.to_owned())
+        };
+        Ok(input)
+    }
+}
+
+/// Check a Title for `rename_input` against the Limits of the Receiver
+///
+/// Titles may contain up to `MAX_INPUT_TITLE_LENGTH` printable ASCII Characters, surrounding Whitespace is removed.
+pub(crate) fn input_title(title: &str) -> Result<&str> {
+    let title = title.trim();
+    if title.is_empty() {
+        return Err(Error::InvalidArgument("Empty input title".to_owned()));
+    }
+    if title.len() > MAX_INPUT_TITLE_LENGTH {
+        return Err(Error::InvalidArgument(format!("Input title {} is longer than {} characters", title, MAX_INPUT_TITLE_LENGTH)));
+    }
+    if let Some(c) = title.chars().find(|c| !(' '..='~').contains(c)) {
+        return Err(Error::InvalidArgument(format!("Invalid character {} in input title {}", c, title)));
+    }
+    Ok(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_id_should_parse_both_spellings() {
+        assert_eq!("HDMI_1".parse::<InputId>().unwrap(), InputId::Hdmi(1));
+        assert_eq!("HDMI1".parse::<InputId>().unwrap(), InputId::Hdmi(1));
+        assert_eq!("av5".parse::<InputId>().unwrap(), InputId::Av(5));
+        assert_eq!("V_AUX".parse::<InputId>().unwrap(), InputId::VAux);
+        assert_eq!("V-AUX".parse::<InputId>().unwrap(), InputId::VAux);
+        assert_eq!("NET RADIO".parse::<InputId>().unwrap(), InputId::NetRadio);
+        assert_eq!("NET_RADIO".parse::<InputId>().unwrap(), InputId::NetRadio);
+        assert_eq!("Deezer".parse::<InputId>().unwrap(), InputId::Other("Deezer".to_owned()));
+        assert!(" ".parse::<InputId>().is_err());
+    }
+
+    #[test]
+    fn input_id_should_render_both_spellings() {
+        for (input, command_name, config_tag) in &[
+            (InputId::Hdmi(1), "HDMI1", "HDMI_1"),
+            (InputId::Audio(2), "AUDIO2", "AUDIO_2"),
+            (InputId::VAux, "V-AUX", "V_AUX"),
+            (InputId::NetRadio, "NET RADIO", "NET_RADIO"),
+            (InputId::MultiCh, "MULTI CH", "MULTI_CH"),
+            (InputId::IpodUsb, "iPod_USB", "iPod_USB"),
+            (InputId::Tuner, "TUNER", "TUNER")
+        ] {
+            assert_eq!(input.command_name(), *command_name);
+            assert_eq!(input.config_tag(), *config_tag);
+            assert_eq!(command_name.parse::<InputId>().unwrap(), *input);
+            assert_eq!(config_tag.parse::<InputId>().unwrap(), *input);
+        }
+    }
+
+    #[test]
+    fn input_title_should_check_limits() {
+        assert_eq!(input_title(" Kodi ").unwrap(), "Kodi");
+        assert_eq!(input_title("Turntable").unwrap(), "Turntable");
+        assert!(input_title("Turntables").is_err());
+        assert!(input_title("   ").is_err());
+        assert!(input_title("Küche").is_err());
+    }
+}
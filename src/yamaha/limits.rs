@@ -0,0 +1,161 @@
+use std::fmt;
+use chrono::NaiveTime;
+use crate::error::{Error, Result};
+use super::volume::Volume;
+
+/// Client side Safety Limits for Volume Changes
+///
+/// Lowering the Volume is always allowed, increases are checked against every configured Limit.
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct VolumeLimits {
+    /// The highest Volume which may be set
+    pub max_volume: Option<Volume>,
+    /// The largest Increase in dB a single Command may cause
+    pub max_step_db: Option<f32>,
+    /// A lower Maximum during the Night
+    pub quiet_hours: Option<QuietHours>
+}
+
+/// A daily Time Window with its own Maximum Volume, may span Midnight
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub max_volume: Volume
+}
+
+impl QuietHours {
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        }else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum VolumeLimitViolation {
+    MaxVolume { requested: Volume, max: Volume },
+    QuietHours { requested: Volume, max: Volume },
+    StepTooLarge { increase_db: f32, max_db: f32 }
+}
+
+impl fmt::Display for VolumeLimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VolumeLimitViolation::MaxVolume { requested, max } => write!(f, "{} exceeds the maximum volume of {}", requested, max),
+            VolumeLimitViolation::QuietHours { requested, max } => write!(f, "{} exceeds the quiet hours maximum of {}", requested, max),
+            VolumeLimitViolation::StepTooLarge { increase_db, max_db } => write!(f, "increase of {} dB exceeds the maximum step of {} dB", increase_db, max_db)
+        }
+    }
+}
+
+impl VolumeLimits {
+    pub fn is_active(&self) -> bool {
+        self.max_volume.is_some() || self.max_step_db.is_some() || self.quiet_hours.is_some()
+    }
+
+    /// The highest Volume allowed at the given Time
+    pub fn ceiling_at(&self, time: NaiveTime) -> Option<Volume> {
+        let quiet = self.quiet_hours
+            .filter(|quiet_hours| quiet_hours.contains(time))
+            .map(|quiet_hours| quiet_hours.max_volume);
+        match (self.max_volume, quiet) {
+            (Some(max), Some(quiet)) => Some(max.min(quiet)),
+            (max, quiet) => max.or(quiet)
+        }
+    }
+
+    /// Check the Change from `current` to `requested` at the given Time
+    pub fn check(&self, current: Volume, requested: Volume, time: NaiveTime) -> Result<()> {
+        if requested <= current {
+            return Ok(());
+        }
+        self.check_ceiling(requested, time)?;
+        if let Some(max_db) = self.max_step_db {
+            let increase_db = requested.db() - current.db();
+            if increase_db > max_db {
+                return Err(Error::VolumeLimitExceeded(VolumeLimitViolation::StepTooLarge { increase_db, max_db }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check `requested` against the Maximum and the Quiet Hours, ignoring the Step Limit
+    pub fn check_ceiling(&self, requested: Volume, time: NaiveTime) -> Result<()> {
+        if let Some(max) = self.max_volume {
+            if requested > max {
+                return Err(Error::VolumeLimitExceeded(VolumeLimitViolation::MaxVolume { requested, max }));
+            }
+        }
+        if let Some(quiet_hours) = self.quiet_hours {
+            if quiet_hours.contains(time) && requested > quiet_hours.max_volume {
+                return Err(Error::VolumeLimitExceeded(VolumeLimitViolation::QuietHours { requested, max: quiet_hours.max_volume }));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn limits() -> VolumeLimits {
+        VolumeLimits {
+            max_volume: Some(Volume::from_db(-10.0)),
+            max_step_db: Some(6.0),
+            quiet_hours: Some(QuietHours {
+                start: time(22, 0),
+                end: time(7, 0),
+                max_volume: Volume::from_db(-40.0)
+            })
+        }
+    }
+
+    #[test]
+    fn quiet_hours_should_span_midnight() {
+        let quiet_hours = limits().quiet_hours.unwrap();
+        assert!(quiet_hours.contains(time(23, 30)));
+        assert!(quiet_hours.contains(time(2, 0)));
+        assert!(!quiet_hours.contains(time(7, 0)));
+        assert!(!quiet_hours.contains(time(12, 0)));
+    }
+
+    #[test]
+    fn check_should_reject_max_volume() {
+        match limits().check(Volume::from_db(-12.0), Volume::from_db(0.0), time(12, 0)) {
+            Err(Error::VolumeLimitExceeded(VolumeLimitViolation::MaxVolume { .. })) => {},
+            other => panic!("Expected MaxVolume, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn check_should_reject_large_steps() {
+        match limits().check(Volume::from_db(-30.0), Volume::from_db(-20.0), time(12, 0)) {
+            Err(Error::VolumeLimitExceeded(VolumeLimitViolation::StepTooLarge { .. })) => {},
+            other => panic!("Expected StepTooLarge, got {:?}", other)
+        }
+        assert!(limits().check(Volume::from_db(-30.0), Volume::from_db(-25.0), time(12, 0)).is_ok());
+    }
+
+    #[test]
+    fn check_should_apply_quiet_hours() {
+        match limits().check(Volume::from_db(-45.0), Volume::from_db(-35.0), time(2, 0)) {
+            Err(Error::VolumeLimitExceeded(VolumeLimitViolation::QuietHours { .. })) => {},
+            other => panic!("Expected QuietHours, got {:?}", other)
+        }
+        assert_eq!(limits().ceiling_at(time(2, 0)), Some(Volume::from_db(-40.0)));
+        assert_eq!(limits().ceiling_at(time(12, 0)), Some(Volume::from_db(-10.0)));
+    }
+
+    #[test]
+    fn check_should_always_allow_decreases() {
+        assert!(limits().check(Volume::from_db(0.0), Volume::from_db(-5.0), time(2, 0)).is_ok());
+    }
+}
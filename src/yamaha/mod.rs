@@ -1,94 +1,492 @@
-use hyper;
-use std::result;
-use std::io::Result;
+use std::time::Duration;
+use tokio::runtime::{Builder, Runtime};
+use crate::error::Result;
 
 mod http;
+mod command;
+mod capabilities;
+mod element;
 mod system_config;
 mod basic_info;
+mod response;
+mod client;
+mod zone;
+mod sleep;
+mod volume;
+mod features;
+mod firmware;
+mod input;
+mod limits;
+mod scene;
+mod sound_program;
+mod tone;
+mod tuner;
+pub mod device_info;
+pub mod discovery;
 
+pub use self::capabilities::{Capabilities, CommandSpec, Subunit, ValueRange};
+pub use self::client::AsyncYamahaAvr;
+pub use self::features::{Feature, Features};
+pub use self::firmware::{FirmwareVersion, VersionNumber};
+pub use self::input::{InputId, MAX_INPUT_TITLE_LENGTH};
+pub use self::system_config::{Input, SystemConfig};
+pub use self::zone::{AsyncZoneControl, Zone};
+pub use self::scene::Scene;
+pub use self::sleep::SleepTimer;
+pub use self::sound_program::SoundProgram;
+pub use self::tone::{Tone, MAX_TONE_DB, MIN_TONE_DB};
+pub use self::tuner::{AsyncTuner, Band, DabInfo, DabService, Frequency, Rds, TunerPreset, TunerStatus, MAX_TUNER_PRESETS};
+pub use self::volume::{CancellationToken, Volume, VolumeRange, VolumeStep, MAX_VOLUME_DB, MIN_VOLUME_DB};
+pub use self::limits::{QuietHours, VolumeLimits, VolumeLimitViolation};
+pub use self::basic_info::{BasicInfo, HdmiOutput, InputInfo, SoundVideo, Surround};
+
+/// Blocking Client for a single Receiver
+///
+/// Thin wrapper around `AsyncYamahaAvr`, each Instance owns a single threaded runtime which runs all of its Commands.
+/// Must not be used from within an async context, use `AsyncYamahaAvr` there instead.
 pub struct YamahaAvr {
-    ip: String
+    runtime: Runtime,
+    inner: AsyncYamahaAvr
 }
 
 impl YamahaAvr {
-    pub fn new(ip: String) -> YamahaAvr {
-        YamahaAvr {
-            ip
-        }
+    pub fn new(ip: String) -> Result<YamahaAvr> {
+        YamahaAvr::from_async(AsyncYamahaAvr::new(ip))
+    }
+
+    pub fn from_async(inner: AsyncYamahaAvr) -> Result<YamahaAvr> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(YamahaAvr {
+            runtime,
+            inner
+        })
+    }
+
+    pub fn as_async(&self) -> &AsyncYamahaAvr {
+        &self.inner
     }
 
-    fn exec(&mut self, xml: String) -> result::Result<String, hyper::Error> {
-        http::exec(self.ip.clone(), xml)
+    pub fn ip(&self) -> &str {
+        self.inner.ip()
     }
 
-    pub fn get_basic_info(&mut self) -> result::Result<basic_info::BasicInfo, hyper::Error> {
-        let cmd = "<YAMAHA_AV cmd=\"GET\"><Main_Zone><Basic_Status>GetParam</Basic_Status></Main_Zone></YAMAHA_AV>".to_owned();
-        let res = self.exec(cmd)?;
-        let info = basic_info::parse_basic_info(res)?;
-        Ok(info)
+    /// The UPnP Device Description as advertised during discovery, None when connected manually
+    pub fn device_info(&self) -> Option<&device_info::DeviceInfo> {
+        self.inner.device_info()
     }
 
-    pub fn get_power(&mut self) -> Result<bool> {
-        let info = self.get_basic_info().unwrap();
-        Ok(info.power)
+    pub fn model_name(&self) -> Option<&str> {
+        self.inner.model_name()
     }
 
-    pub fn set_power(&mut self, value: bool) -> Result<()> {
-        let cmd = format!("<YAMAHA_AV cmd=\"PUT\"><Main_Zone><Power_Control><Power>{}</Power></Power_Control></Main_Zone></YAMAHA_AV>", if value { "On" } else { "Standby" });
-        self.exec(cmd).unwrap();
-        Ok(())
+    /// The URL commands are sent to
+    pub fn control_url(&self) -> &str {
+        self.inner.control_url()
     }
 
-    pub fn get_mute(&mut self) -> Result<bool> {
-        let info = self.get_basic_info().unwrap();
-        Ok(info.mute)
+    /// The URL of the Unit Description
+    pub fn desc_url(&self) -> &str {
+        self.inner.desc_url()
     }
 
-    pub fn set_mute(&mut self, value: bool) -> Result<()> {
-        let cmd = format!("<YAMAHA_AV cmd=\"PUT\"><Main_Zone><Volume><Mute>{}</Mute></Volume></Main_Zone></YAMAHA_AV>", if value { "On" } else { "Off" });
-        self.exec(cmd).unwrap();
-        Ok(())
+    /// The cached Unit Description, None until `load_capabilities` was called
+    pub fn capabilities(&self) -> Option<&Capabilities> {
+        self.inner.capabilities()
     }
 
-    pub fn get_volume(&mut self) -> Result<i32> {
-        let info = self.get_basic_info().unwrap();
-        Ok(info.volume)
+    /// Fetch and parse the Unit Description without caching it
+    pub fn get_capabilities(&self) -> Result<Capabilities> {
+        self.runtime.block_on(self.inner.get_capabilities())
     }
 
-    pub fn set_volume(&mut self, value: i32) -> Result<()> {
-        let cmd = format!("<YAMAHA_AV cmd=\"PUT\"><Main_Zone><Volume><Lvl><Val>{}</Val><Exp>1</Exp><Unit>dB</Unit></Lvl></Volume></Main_Zone></YAMAHA_AV>", value);
-        self.exec(cmd).unwrap();
-        Ok(())
+    /// Fetch the Unit Description and cache it, all following Commands are validated against it
+    pub fn load_capabilities(&mut self) -> Result<&Capabilities> {
+        self.runtime.block_on(self.inner.load_capabilities())
     }
 
-    /// Select a Input for the given Zone or Main_Zone when zone is None.
+    /// See `AsyncYamahaAvr::volume_range`
+    pub fn volume_range(&self) -> VolumeRange {
+        self.inner.volume_range()
+    }
+
+    /// See `AsyncYamahaAvr::zone_volume_range`
+    pub fn zone_volume_range(&self, zone: Zone) -> VolumeRange {
+        self.inner.zone_volume_range(zone)
+    }
+
+    /// Override the Range of all Zones, takes precedence over the Unit Description
+    pub fn set_volume_range(&mut self, range: VolumeRange) {
+        self.inner.set_volume_range(range)
+    }
+
+    /// The Limits all Volume Changes are checked against
+    pub fn volume_limits(&self) -> VolumeLimits {
+        self.inner.volume_limits()
+    }
+
+    /// Reject Volume Changes exceeding the given Limits with `Error::VolumeLimitExceeded`
+    pub fn set_volume_limits(&mut self, limits: VolumeLimits) {
+        self.inner.set_volume_limits(limits)
+    }
+
+    pub fn main_zone(&self) -> ZoneControl<'_> {
+        ZoneControl {
+            runtime: &self.runtime,
+            inner: self.inner.main_zone()
+        }
+    }
+
+    /// Control the given Zone
     ///
-    /// Does nothing when Input is not available
+    /// Fails with `Error::ZoneNotAvailable` when the Receiver does not have this Zone
+    pub fn zone(&self, zone: Zone) -> Result<ZoneControl<'_>> {
+        let inner = self.runtime.block_on(self.inner.zone(zone))?;
+        Ok(ZoneControl {
+            runtime: &self.runtime,
+            inner
+        })
+    }
+
+    /// Control the FM/AM Tuner
     ///
-    /// # Arguments
+    /// Fails with `Error::Unsupported` when the Receiver has no Tuner
+    pub fn tuner(&self) -> Result<Tuner<'_>> {
+        let inner = self.runtime.block_on(self.inner.tuner())?;
+        Ok(Tuner {
+            runtime: &self.runtime,
+            inner
+        })
+    }
+
+    pub fn get_basic_info(&self) -> Result<BasicInfo> {
+        self.runtime.block_on(self.inner.get_basic_info())
+    }
+
+    pub fn get_power(&self) -> Result<bool> {
+        self.runtime.block_on(self.inner.get_power())
+    }
+
+    pub fn set_power(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_power(value))
+    }
+
+    pub fn get_mute(&self) -> Result<bool> {
+        self.runtime.block_on(self.inner.get_mute())
+    }
+
+    pub fn set_mute(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_mute(value))
+    }
+
+    pub fn get_volume(&self) -> Result<Volume> {
+        self.runtime.block_on(self.inner.get_volume())
+    }
+
+    pub fn set_volume(&self, value: Volume) -> Result<()> {
+        self.runtime.block_on(self.inner.set_volume(value))
+    }
+
+    pub fn volume_up(&self, step: VolumeStep) -> Result<()> {
+        self.runtime.block_on(self.inner.volume_up(step))
+    }
+
+    pub fn volume_down(&self, step: VolumeStep) -> Result<()> {
+        self.runtime.block_on(self.inner.volume_down(step))
+    }
+
+    /// Change the Volume by `delta_db`, rounded to 0.5 dB and sent as the fewest relative Steps
+    pub fn change_volume(&self, delta_db: f32) -> Result<()> {
+        self.runtime.block_on(self.inner.change_volume(delta_db))
+    }
+
+    /// Gradually change the Volume to `target` over `duration`, blocking until done
     ///
-    /// * `input` - The Input to select
-    /// * `zone` - The Zone in which the input should be selected. Defaults to Main_Zone
+    /// Stops early when `cancel` is cancelled from another Thread and returns the Volume which was set last.
+    pub fn fade_volume(&self, target: Volume, duration: Duration, cancel: &CancellationToken) -> Result<Volume> {
+        self.runtime.block_on(self.inner.fade_volume(target, duration, cancel))
+    }
+
+    /// Select a Input for the Main_Zone, use `zone` to select the Input of other Zones.
     ///
-    pub fn select_input(&mut self, input: String, zone: Option<&str>) -> Result<()> {
-        let cmd = format!("<YAMAHA_AV cmd=\"PUT\"><{zone}><Input><Input_Sel>{}</Input_Sel></Input></{zone}></YAMAHA_AV>", input, zone = zone.unwrap_or("Main_Zone"));
-        self.exec(cmd).unwrap();
-        Ok(())
+    /// See `AsyncYamahaAvr::select_input`
+    pub fn select_input(&self, input: &InputId) -> Result<()> {
+        self.runtime.block_on(self.inner.select_input(input))
+    }
+
+    /// See `AsyncYamahaAvr::select_input_by_name`
+    pub fn select_input_by_name(&self, name: &str) -> Result<InputId> {
+        self.runtime.block_on(self.inner.select_input_by_name(name))
+    }
+
+    /// Whether HDMI Signals are passed through while the Receiver is in Standby, None when not reported
+    pub fn get_hdmi_standby_through(&self) -> Result<Option<bool>> {
+        self.runtime.block_on(self.inner.get_hdmi_standby_through())
+    }
+
+    /// Pass HDMI Signals through while the Receiver is in Standby, this is a System wide Setting
+    pub fn set_hdmi_standby_through(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_hdmi_standby_through(value))
+    }
+
+    /// See `AsyncYamahaAvr::rename_input`
+    pub fn rename_input(&self, input: &InputId, title: &str) -> Result<()> {
+        self.runtime.block_on(self.inner.rename_input(input, title))
+    }
+
+    pub fn get_inputs(&self) -> Result<Vec<system_config::Input>> {
+        self.runtime.block_on(self.inner.get_inputs())
+    }
+
+    pub fn get_zones(&self) -> Result<Vec<String>> {
+        self.runtime.block_on(self.inner.get_zones())
+    }
+
+    /// All Features reported in the System Config
+    pub fn get_features(&self) -> Result<Features> {
+        self.runtime.block_on(self.inner.get_features())
+    }
+
+    /// Whether the Receiver has the given Feature, e.g. `Feature::Tuner`
+    pub fn supports(&self, feature: &Feature) -> Result<bool> {
+        self.runtime.block_on(self.inner.supports(feature))
+    }
+
+    pub fn get_system_config(&self) -> Result<system_config::SystemConfig> {
+        self.runtime.block_on(self.inner.get_system_config())
+    }
+}
+
+/// Blocking variant of `AsyncZoneControl`
+pub struct ZoneControl<'a> {
+    runtime: &'a Runtime,
+    inner: AsyncZoneControl<'a>
+}
+
+impl<'a> ZoneControl<'a> {
+    pub fn zone(&self) -> Zone {
+        self.inner.zone()
+    }
+
+    pub fn get_basic_info(&self) -> Result<BasicInfo> {
+        self.runtime.block_on(self.inner.get_basic_info())
+    }
+
+    pub fn get_power(&self) -> Result<bool> {
+        self.runtime.block_on(self.inner.get_power())
+    }
+
+    pub fn set_power(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_power(value))
     }
 
-    pub fn get_inputs(&mut self) -> Result<Vec<system_config::Input>> {
-        let config = self.get_system_config()?;
-        Ok(config.inputs)
+    pub fn get_mute(&self) -> Result<bool> {
+        self.runtime.block_on(self.inner.get_mute())
     }
 
-    pub fn get_zones(&mut self) -> Result<Vec<String>> {
-        let config = self.get_system_config()?;
-        Ok(config.available_zones)
+    pub fn set_mute(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_mute(value))
     }
 
-    pub fn get_system_config(&mut self) -> Result<system_config::SystemConfig> {
-        let cmd = "<YAMAHA_AV cmd=\"GET\"><System><Config>GetParam</Config></System></YAMAHA_AV>".to_owned();
-        let res = self.exec(cmd).unwrap();
-        system_config::parse_system_config(res)
+    pub fn get_volume(&self) -> Result<Volume> {
+        self.runtime.block_on(self.inner.get_volume())
     }
-}
\ No newline at end of file
+
+    pub fn set_volume(&self, value: Volume) -> Result<()> {
+        self.runtime.block_on(self.inner.set_volume(value))
+    }
+
+    pub fn volume_up(&self, step: VolumeStep) -> Result<()> {
+        self.runtime.block_on(self.inner.volume_up(step))
+    }
+
+    pub fn volume_down(&self, step: VolumeStep) -> Result<()> {
+        self.runtime.block_on(self.inner.volume_down(step))
+    }
+
+    /// Change the Volume by `delta_db`, rounded to 0.5 dB and sent as the fewest relative Steps
+    pub fn change_volume(&self, delta_db: f32) -> Result<()> {
+        self.runtime.block_on(self.inner.change_volume(delta_db))
+    }
+
+    /// Gradually change the Volume to `target` over `duration`, blocking until done
+    ///
+    /// Stops early when `cancel` is cancelled from another Thread and returns the Volume which was set last.
+    pub fn fade_volume(&self, target: Volume, duration: Duration, cancel: &CancellationToken) -> Result<Volume> {
+        self.runtime.block_on(self.inner.fade_volume(target, duration, cancel))
+    }
+
+    /// See `AsyncZoneControl::sound_programs`
+    pub fn sound_programs(&self) -> Vec<SoundProgram> {
+        self.inner.sound_programs()
+    }
+
+    /// The current DSP Program, None for Zones without DSP
+    pub fn get_sound_program(&self) -> Result<Option<SoundProgram>> {
+        self.runtime.block_on(self.inner.get_sound_program())
+    }
+
+    pub fn set_sound_program(&self, program: SoundProgram) -> Result<()> {
+        self.runtime.block_on(self.inner.set_sound_program(program))
+    }
+
+    pub fn set_straight(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_straight(value))
+    }
+
+    pub fn set_enhancer(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_enhancer(value))
+    }
+
+    pub fn set_3d_cinema_dsp(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_3d_cinema_dsp(value))
+    }
+
+    /// Bass and Treble, None for Zones without Tone Control
+    pub fn get_tone(&self) -> Result<Option<Tone>> {
+        self.runtime.block_on(self.inner.get_tone())
+    }
+
+    /// Set the Bass Level in dB, see `AsyncZoneControl::set_bass`
+    pub fn set_bass(&self, db: f32) -> Result<()> {
+        self.runtime.block_on(self.inner.set_bass(db))
+    }
+
+    /// Set the Treble Level in dB, see `AsyncZoneControl::set_treble`
+    pub fn set_treble(&self, db: f32) -> Result<()> {
+        self.runtime.block_on(self.inner.set_treble(db))
+    }
+
+    /// Pure Direct, None for Zones without this Setting
+    pub fn get_pure_direct(&self) -> Result<Option<bool>> {
+        self.runtime.block_on(self.inner.get_pure_direct())
+    }
+
+    pub fn set_pure_direct(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_pure_direct(value))
+    }
+
+    /// Adaptive DRC, None for Zones without this Setting
+    pub fn get_adaptive_drc(&self) -> Result<Option<bool>> {
+        self.runtime.block_on(self.inner.get_adaptive_drc())
+    }
+
+    pub fn set_adaptive_drc(&self, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_adaptive_drc(value))
+    }
+
+    /// The HDMI Outputs of this Zone, empty when the Zone does not report them
+    pub fn get_hdmi_outputs(&self) -> Result<Vec<HdmiOutput>> {
+        self.runtime.block_on(self.inner.get_hdmi_outputs())
+    }
+
+    /// Enable or disable a HDMI Output, starting at 1
+    pub fn set_hdmi_output(&self, output: u8, value: bool) -> Result<()> {
+        self.runtime.block_on(self.inner.set_hdmi_output(output, value))
+    }
+
+    pub fn get_input(&self) -> Result<InputId> {
+        self.runtime.block_on(self.inner.get_input())
+    }
+
+    pub fn select_input(&self, input: &InputId) -> Result<()> {
+        self.runtime.block_on(self.inner.select_input(input))
+    }
+
+    pub fn select_input_by_name(&self, name: &str) -> Result<InputId> {
+        self.runtime.block_on(self.inner.select_input_by_name(name))
+    }
+
+    pub fn get_sleep(&self) -> Result<SleepTimer> {
+        self.runtime.block_on(self.inner.get_sleep())
+    }
+
+    pub fn set_sleep(&self, value: SleepTimer) -> Result<()> {
+        self.runtime.block_on(self.inner.set_sleep(value))
+    }
+
+    /// The Scenes of this Zone with their Titles
+    pub fn get_scenes(&self) -> Result<Vec<Scene>> {
+        self.runtime.block_on(self.inner.get_scenes())
+    }
+
+    /// Recall one of the Scenes of this Zone, starting at 1
+    pub fn recall_scene(&self, scene: u8) -> Result<()> {
+        self.runtime.block_on(self.inner.recall_scene(scene))
+    }
+}
+
+/// Blocking Control of the Tuner, see `AsyncTuner`
+pub struct Tuner<'a> {
+    runtime: &'a Runtime,
+    inner: AsyncTuner<'a>
+}
+
+impl<'a> Tuner<'a> {
+    pub fn get_status(&self) -> Result<TunerStatus> {
+        self.runtime.block_on(self.inner.get_status())
+    }
+
+    pub fn get_band(&self) -> Result<Band> {
+        self.runtime.block_on(self.inner.get_band())
+    }
+
+    pub fn set_band(&self, band: Band) -> Result<()> {
+        self.runtime.block_on(self.inner.set_band(band))
+    }
+
+    pub fn get_frequency(&self) -> Result<Frequency> {
+        self.runtime.block_on(self.inner.get_frequency())
+    }
+
+    pub fn set_frequency(&self, frequency: Frequency) -> Result<()> {
+        self.runtime.block_on(self.inner.set_frequency(frequency))
+    }
+
+    pub fn seek_up(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.seek_up())
+    }
+
+    pub fn seek_down(&self) -> Result<()> {
+        self.runtime.block_on(self.inner.seek_down())
+    }
+
+    pub fn get_presets(&self) -> Result<Vec<TunerPreset>> {
+        self.runtime.block_on(self.inner.get_presets())
+    }
+
+    pub fn recall_preset(&self, preset: u8) -> Result<()> {
+        self.runtime.block_on(self.inner.recall_preset(preset))
+    }
+
+    pub fn store_preset(&self, preset: u8) -> Result<()> {
+        self.runtime.block_on(self.inner.store_preset(preset))
+    }
+
+    pub fn get_rds(&self) -> Result<Option<Rds>> {
+        self.runtime.block_on(self.inner.get_rds())
+    }
+
+    pub fn get_dab_services(&self) -> Result<Vec<DabService>> {
+        self.runtime.block_on(self.inner.get_dab_services())
+    }
+
+    pub fn select_dab_service(&self, service: u16) -> Result<()> {
+        self.runtime.block_on(self.inner.select_dab_service(service))
+    }
+
+    pub fn get_dab_presets(&self) -> Result<Vec<TunerPreset>> {
+        self.runtime.block_on(self.inner.get_dab_presets())
+    }
+
+    pub fn recall_dab_preset(&self, preset: u8) -> Result<()> {
+        self.runtime.block_on(self.inner.recall_dab_preset(preset))
+    }
+
+    pub fn store_dab_preset(&self, preset: u8) -> Result<()> {
+        self.runtime.block_on(self.inner.store_dab_preset(preset))
+    }
+
+    pub fn get_dab_info(&self) -> Result<Option<DabInfo>> {
+        self.runtime.block_on(self.inner.get_dab_info())
+    }
+}
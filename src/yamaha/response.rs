@@ -0,0 +1,57 @@
+use crate::error::{Error, Result};
+use super::element::Element;
+
+/// Check the `RC` Attribute of a Response and turn receiver side failures into Errors
+pub fn check_response_code(xml: &str) -> Result<()> {
+    let root = Element::parse(xml)?;
+    if root.name() != "YAMAHA_AV" {
+        return Err(Error::UnexpectedResponse(format!("Unexpected root element {}", root.name())));
+    }
+    let code = root.attribute("RC")
+        .ok_or_else(|| Error::UnexpectedResponse("Missing RC attribute".to_owned()))?;
+    let code: u32 = code.parse()
+        .map_err(|_| Error::UnexpectedResponse(format!("Invalid RC attribute {}", code)))?;
+    match code {
+        0 => Ok(()),
+        1 => Err(Error::NotAvailable),
+        2 => Err(Error::InvalidCommand),
+        3 => Err(Error::InvalidValue),
+        4 => Err(Error::Busy),
+        code => Err(Error::Receiver(code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_response_code_should_accept_success() {
+        let input = "<YAMAHA_AV rsp=\"PUT\" RC=\"0\"><Main_Zone><Volume><Mute></Mute></Volume></Main_Zone></YAMAHA_AV>";
+        assert!(check_response_code(input).is_ok());
+    }
+
+    #[test]
+    fn check_response_code_should_map_known_codes() {
+        let input = "<YAMAHA_AV rsp=\"PUT\" RC=\"3\"><Main_Zone><Input><Input_Sel></Input_Sel></Input></Main_Zone></YAMAHA_AV>";
+        match check_response_code(input) {
+            Err(Error::InvalidValue) => {},
+            other => panic!("Expected InvalidValue, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn check_response_code_should_keep_unknown_codes() {
+        let input = "<YAMAHA_AV rsp=\"PUT\" RC=\"7\"></YAMAHA_AV>";
+        match check_response_code(input) {
+            Err(Error::Receiver(7)) => {},
+            other => panic!("Expected Receiver(7), got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn check_response_code_should_fail_without_code() {
+        let input = "<YAMAHA_AV rsp=\"GET\"></YAMAHA_AV>";
+        assert!(check_response_code(input).is_err());
+    }
+}
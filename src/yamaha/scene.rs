@@ -0,0 +1,47 @@
+use crate::error::{Error, Result};
+use super::element::Element;
+
+/// A Scene of a Zone as listed in `Scene_Sel_Item`
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Scene {
+    /// The Number used for `recall_scene`, starting at 1
+    pub number: u8,
+    /// The Title shown by the Receiver, e.g. `BD/DVD`
+    pub name: String,
+    /// False when the Scene is not configured or not supported by this Zone
+    pub available: bool
+}
+
+pub fn parse_scenes(xml: String) -> Result<Vec<Scene>> {
+    let root = Element::parse(&xml)?;
+    let items = root.children().iter()
+        .find_map(|zone| zone.find("Scene/Scene_Sel_Item"))
+        .ok_or_else(|| Error::UnexpectedResponse("Missing Scene_Sel_Item".to_owned()))?;
+    items.children().iter()
+        .map(|item| {
+            let number = item.name().strip_prefix("Item_")
+                .and_then(|number| number.parse().ok())
+                .ok_or_else(|| Error::UnexpectedResponse(format!("Invalid scene item {}", item.name())))?;
+            Ok(Scene {
+                number,
+                name: item.find_text("Title").unwrap_or_default().trim().to_owned(),
+                available: item.find_text("RW").map(str::trim) != Some("---")
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_scenes_should_parse_items() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Main_Zone><Scene><Scene_Sel_Item><Item_1><Param>Scene 1</Param><RW>W</RW><Title>BD/DVD </Title><Icon><On>/YamahaRemoteControl/Icons/icon002.png</On><Off></Off></Icon><Src_Name></Src_Name><Src_Number>1</Src_Number></Item_1><Item_2><Param>Scene 2</Param><RW>---</RW><Title>TV</Title><Icon><On></On><Off></Off></Icon><Src_Name></Src_Name><Src_Number>2</Src_Number></Item_2></Scene_Sel_Item></Scene></Main_Zone></YAMAHA_AV>");
+        let scenes = parse_scenes(input).unwrap();
+        assert_eq!(scenes, vec![
+            Scene { number: 1, name: "BD/DVD".to_owned(), available: true },
+            Scene { number: 2, name: "TV".to_owned(), available: false }
+        ]);
+    }
+}
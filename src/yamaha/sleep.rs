@@ -0,0 +1,86 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::error::Error;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum SleepTimer {
+    Off,
+    Min30,
+    Min60,
+    Min90,
+    Min120
+}
+
+impl SleepTimer {
+    /// The Value used by the Receiver, e.g. `60 min`
+    pub fn value(self) -> &'static str {
+        match self {
+            SleepTimer::Off => "Off",
+            SleepTimer::Min30 => "30 min",
+            SleepTimer::Min60 => "60 min",
+            SleepTimer::Min90 => "90 min",
+            SleepTimer::Min120 => "120 min"
+        }
+    }
+
+    /// The remaining Minutes, None when the Timer is off
+    pub fn minutes(self) -> Option<u32> {
+        match self {
+            SleepTimer::Off => None,
+            SleepTimer::Min30 => Some(30),
+            SleepTimer::Min60 => Some(60),
+            SleepTimer::Min90 => Some(90),
+            SleepTimer::Min120 => Some(120)
+        }
+    }
+
+    pub fn from_minutes(minutes: u32) -> Option<SleepTimer> {
+        match minutes {
+            0 => Some(SleepTimer::Off),
+            30 => Some(SleepTimer::Min30),
+            60 => Some(SleepTimer::Min60),
+            90 => Some(SleepTimer::Min90),
+            120 => Some(SleepTimer::Min120),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for SleepTimer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.value())
+    }
+}
+
+/// Parses the Receiver Values (`Off`, `30 min`) as well as plain Minutes (`30`)
+impl FromStr for SleepTimer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SleepTimer, Error> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("off") {
+            return Ok(SleepTimer::Off);
+        }
+        s.trim_end_matches("min").trim().parse()
+            .ok()
+            .and_then(SleepTimer::from_minutes)
+            .ok_or_else(|| Error::InvalidArgument(format!("Invalid sleep timer {}", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sleep_timer_should_parse_receiver_values() {
+        assert_eq!("Off".parse::<SleepTimer>().unwrap(), SleepTimer::Off);
+        assert_eq!("90 min".parse::<SleepTimer>().unwrap(), SleepTimer::Min90);
+    }
+
+    #[test]
+    fn sleep_timer_should_parse_minutes() {
+        assert_eq!("60".parse::<SleepTimer>().unwrap(), SleepTimer::Min60);
+        assert!("45".parse::<SleepTimer>().is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::error::Error;
+
+/// The DSP Programs documented for the RX Series
+///
+/// Not every Model supports every Program, unknown Programs reported by the Receiver are kept as `Other`.
+#[derive(PartialEq, Eq, Debug, Clone, Hash)]
+pub enum SoundProgram {
+    HallInMunich,
+    HallInVienna,
+    HallInAmsterdam,
+    ChurchInFreiburg,
+    ChurchInRoyaumont,
+    Chamber,
+    VillageVanguard,
+    WarehouseLoft,
+    CellarClub,
+    TheRoxyTheatre,
+    TheBottomLine,
+    Sports,
+    ActionGame,
+    RoleplayingGame,
+    MusicVideo,
+    RecitalOpera,
+    Standard,
+    Spectacle,
+    SciFi,
+    Adventure,
+    Drama,
+    MonoMovie,
+    Stereo2ch,
+    Stereo5ch,
+    Stereo7ch,
+    Stereo9ch,
+    SurroundDecoder,
+    Other(String)
+}
+
+impl SoundProgram {
+    /// All documented Programs, excluding `Other`
+    pub fn all() -> Vec<SoundProgram> {
+        vec![
+            SoundProgram::HallInMunich,
+            SoundProgram::HallInVienna,
+            SoundProgram::HallInAmsterdam,
+            SoundProgram::ChurchInFreiburg,
+            SoundProgram::ChurchInRoyaumont,
+            SoundProgram::Chamber,
+            SoundProgram::VillageVanguard,
+            SoundProgram::WarehouseLoft,
+            SoundProgram::CellarClub,
+            SoundProgram::TheRoxyTheatre,
+            SoundProgram::TheBottomLine,
+            SoundProgram::Sports,
+            SoundProgram::ActionGame,
+            SoundProgram::RoleplayingGame,
+            SoundProgram::MusicVideo,
+            SoundProgram::RecitalOpera,
+            SoundProgram::Standard,
+            SoundProgram::Spectacle,
+            SoundProgram::SciFi,
+            SoundProgram::Adventure,
+            SoundProgram::Drama,
+            SoundProgram::MonoMovie,
+            SoundProgram::Stereo2ch,
+            SoundProgram::Stereo5ch,
+            SoundProgram::Stereo7ch,
+            SoundProgram::Stereo9ch,
+            SoundProgram::SurroundDecoder
+        ]
+    }
+
+    /// The Value used by the Receiver, e.g. `Hall in Munich`
+    pub fn value(&self) -> &str {
+        match *self {
+            SoundProgram::HallInMunich => "Hall in Munich",
+            SoundProgram::HallInVienna => "Hall in Vienna",
+            SoundProgram::HallInAmsterdam => "Hall in Amsterdam",
+            SoundProgram::ChurchInFreiburg => "Church in Freiburg",
+            SoundProgram::ChurchInRoyaumont => "Church in Royaumont",
+            SoundProgram::Chamber => "Chamber",
+            SoundProgram::VillageVanguard => "Village Vanguard",
+            SoundProgram::WarehouseLoft => "Warehouse Loft",
+            SoundProgram::CellarClub => "Cellar Club",
+            SoundProgram::TheRoxyTheatre => "The Roxy Theatre",
+            SoundProgram::TheBottomLine => "The Bottom Line",
+            SoundProgram::Sports => "Sports",
+            SoundProgram::ActionGame => "Action Game",
+            SoundProgram::RoleplayingGame => "Roleplaying Game",
+            SoundProgram::MusicVideo => "Music Video",
+            SoundProgram::RecitalOpera => "Recital/Opera",
+            SoundProgram::Standard => "Standard",
+            SoundProgram::Spectacle => "Spectacle",
+            SoundProgram::SciFi => "Sci-Fi",
+            SoundProgram::Adventure => "Adventure",
+            SoundProgram::Drama => "Drama",
+            SoundProgram::MonoMovie => "Mono Movie",
+            SoundProgram::Stereo2ch => "2ch Stereo",
+            SoundProgram::Stereo5ch => "5ch Stereo",
+            SoundProgram::Stereo7ch => "7ch Stereo",
+            SoundProgram::Stereo9ch => "9ch Stereo",
+            SoundProgram::SurroundDecoder => "Surround Decoder",
+            SoundProgram::Other(ref value) => value
+        }
+    }
+
+    /// Map a Value reported by the Receiver, unknown Values become `Other`
+    pub fn from_value(value: &str) -> SoundProgram {
+        SoundProgram::all()
+            .into_iter()
+            .find(|program| program.value() == value)
+            .unwrap_or_else(|| SoundProgram::Other(value.to_owned()))
+    }
+}
+
+impl fmt::Display for SoundProgram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.value())
+    }
+}
+
+/// Parses the Receiver Values ignoring case, e.g. `hall in munich`
+///
+/// Unknown Programs are passed to the Receiver as `Other`, which rejects them with `Error::InvalidValue`.
+impl FromStr for SoundProgram {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SoundProgram, Error> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(Error::InvalidArgument("Empty sound program".to_owned()));
+        }
+        let program = SoundProgram::all()
+            .into_iter()
+            .find(|program| program.value().eq_ignore_ascii_case(s))
+            .unwrap_or_else(|| SoundProgram::Other(s.to_owned()));
+        Ok(program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_value_should_map_known_programs() {
+        assert_eq!(SoundProgram::from_value("5ch Stereo"), SoundProgram::Stereo5ch);
+        assert_eq!(SoundProgram::from_value("Sci-Fi"), SoundProgram::SciFi);
+        assert_eq!(SoundProgram::from_value("Straight Enhancer"), SoundProgram::Other("Straight Enhancer".to_owned()));
+    }
+
+    #[test]
+    fn sound_program_should_parse_ignoring_case() {
+        assert_eq!("hall in munich".parse::<SoundProgram>().unwrap(), SoundProgram::HallInMunich);
+        assert_eq!("Recital/Opera".parse::<SoundProgram>().unwrap().value(), "Recital/Opera");
+        assert!("".parse::<SoundProgram>().is_err());
+    }
+}
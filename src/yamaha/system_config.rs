@@ -1,167 +1,101 @@
-extern crate xml;
-extern crate regex;
-
-use std::io::Result;
-
-#[derive(PartialEq, Debug, Copy, Clone)]
-pub struct SystemConfigAvailableFeatures {
-    pub tuner: bool,
-    pub hd_radio: bool,
-    pub rhapsody: bool,
-    pub sirius_ir: bool,
-    pub pandora: bool,
-    pub server: bool,
-    pub net_radio: bool,
-    pub usb: bool,
-    pub ipod: bool,
-    pub air_play: bool
-}
+use std::fmt;
+use crate::error::{Error, Result};
+use super::element::Element;
+use super::features::{Feature, Features};
+use super::firmware::FirmwareVersion;
+use super::input::InputId;
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct SystemConfig {
     pub model_name: Option<String>,
+    /// Identifies the Receiver independently of its IP, e.g. `05852093`
+    pub system_id: Option<String>,
+    /// None when the Version is missing or not in the `main/sub` Format
+    pub firmware_version: Option<FirmwareVersion>,
     pub inputs: Vec<Input>,
     pub available_zones: Vec<String>,
-    pub available_features: SystemConfigAvailableFeatures
+    /// All Entries of `Feature_Existence`, including the Zones
+    pub features: Features
 }
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct Input {
     /// The Internal name, used for input selection
-    pub name: String,
+    pub id: InputId,
     /// A User set Display Name, None when the name is empty
     pub display_name: Option<String>
 }
 
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.display_name {
+            Some(ref display_name) => write!(f, "{} ({})", self.id, display_name),
+            None => write!(f, "{}", self.id)
+        }
+    }
+}
+
+/// Find the Input with the given Display Name
+///
+/// Names are compared case-insensitively and without surrounding Whitespace, a Prefix is enough when
+/// only one Input starts with it. Exact Matches are preferred over Prefix Matches.
+pub(crate) fn find_input<'a>(inputs: &'a [Input], name: &str) -> Result<&'a Input> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(Error::InvalidArgument("Empty input name".to_owned()));
+    }
+    let display_name = |input: &Input| input.display_name.as_ref().map(|display_name| display_name.trim().to_lowercase());
+    let exact: Vec<&Input> = inputs.iter()
+        .filter(|input| display_name(input).as_ref() == Some(&name))
+        .collect();
+    let candidates = if exact.is_empty() {
+        inputs.iter()
+            .filter(|input| display_name(input).map(|display_name| display_name.starts_with(&name)).unwrap_or(false))
+            .collect()
+    }else {
+        exact
+    };
+    match candidates.as_slice() {
+        [] => Err(Error::InvalidArgument(format!("No input named {}", name))),
+        [input] => Ok(input),
+        _ => Err(Error::AmbiguousInput(candidates.into_iter().cloned().collect()))
+    }
+}
+
 pub fn parse_system_config(xml: String) -> Result<SystemConfig> {
-    let reader = xml::reader::EventReader::from_str(&xml);
-    let mut inputs: Vec<Input> = Vec::new();
-    let mut in_inputs = false;
-    let mut current_input: Option<Input> = None;
-    let mut in_model_name = false;
-    let mut model_name: Option<String> = None;
-    let mut in_feature_existence = false;
-    let mut current_feature: Option<String> = None;
+    let root = Element::parse(&xml)?;
+    let config = root.find("System/Config")
+        .ok_or_else(|| Error::UnexpectedResponse("Missing System/Config".to_owned()))?;
     let mut available_zones: Vec<String> = Vec::new();
-    let mut available_features = SystemConfigAvailableFeatures {
-        tuner: false,
-        hd_radio: false,
-        rhapsody: false,
-        sirius_ir: false,
-        pandora: false,
-        server: false,
-        net_radio: false,
-        usb: false,
-        ipod: false,
-        air_play: false
-    };
-    for e in reader {
-        match e {
-            Ok(xml::reader::XmlEvent::StartElement { name, .. }) => {
-                match name.local_name.as_str() {
-                    "Input" => {
-                        in_inputs = true;
-                    }
-                    "Model_Name" => {
-                        in_model_name = true;
-                    }
-                    "Feature_Existence" => {
-                        in_feature_existence = true;
-                    }
-                    _ => {
-                        if in_inputs {
-                            current_input = Some(Input {
-                                name: name.local_name,
-                                display_name: None
-                            });
-                        }else if in_feature_existence {
-                            current_feature = Some(name.local_name);
-                        }
-                    }
-                }
-            }
-            Ok(xml::reader::XmlEvent::EndElement { name, .. }) => {
-                match name.local_name.as_str() {
-                    "Input" => {
-                        in_inputs = false
-                    }
-                    "Model_Name" => {
-                        in_model_name = false
-                    }
-                    "Feature_Existence" => {
-                        in_feature_existence = false;
-                    }
-                    _ => {
-                        if in_inputs {
-                            inputs.push(current_input.unwrap());
-                            current_input = None;
-                        }else if in_feature_existence {
-                            current_feature = None;
-                        }
-                    }
-                }
+    let mut features = Features::default();
+    for element in config.find("Feature_Existence").map(Element::children).unwrap_or_default() {
+        let feature = Feature::from_tag(element.name());
+        let exists = element.text().trim() == "1";
+        if exists {
+            if let Feature::MainZone | Feature::Zone2 | Feature::Zone3 | Feature::Zone4 = feature {
+                available_zones.push(element.name().to_owned());
             }
-            Ok(xml::reader::XmlEvent::Characters(s)) => {
-                if in_model_name {
-                    model_name = Some(s.trim().to_owned());
-                }else if in_inputs && current_input.is_some() {
-                    current_input = Some(Input {
-                        name: current_input.unwrap().name,
-                        display_name: Some(s.trim().to_owned())
-                    });
-                }else if in_feature_existence {
-                    if s == "1" {
-                        match current_feature.as_ref().map(String::as_ref) {
-                            Some("Main_Zone") => available_zones.push("Main_Zone".to_owned()),
-                            Some("Zone_2") => available_zones.push("Zone_2".to_owned()),
-                            Some("Zone_3") => available_zones.push("Zone_3".to_owned()),
-                            Some("Tuner") => available_features.tuner = true,
-                            Some("Zone_4") => available_zones.push("Zone_4".to_owned()),
-                            Some("HD_Radio") => available_features.hd_radio = true,
-                            Some("Rhapsody") => available_features.rhapsody = true,
-                            Some("SIRIUS_IR") => available_features.sirius_ir = true,
-                            Some("Pandora") => available_features.pandora = true,
-                            Some("SERVER") => available_features.server = true,
-                            Some("NET_RADIO") => available_features.net_radio = true,
-                            Some("USB") => available_features.usb = true,
-                            Some("iPod_USB") => available_features.ipod = true,
-                            Some("AirPlay") => available_features.air_play = true,
-                            _ => {}
-                        }
-                    }
-                }
-            }
-            _ => {}
         }
+        features.insert(feature, exists);
     }
 
-    let regex = regex::Regex::new(r"^([A-Z]+)_([0-9]+)$").unwrap();
-
-    let inputs: Vec<Input> = inputs.iter_mut()
+    let inputs = config.find("Name/Input").map(Element::children).unwrap_or_default();
+    let inputs: Vec<Input> = inputs.iter()
         .map(|input| {
-            let input = input.clone();
-            let mut name: String = input.name;
-            if name == "V_AUX" {
-                name = "V-AUX".to_owned();
-            }else {
-                let input_name = name.clone();
-                let name_parts = regex.captures(&input_name);
-                if name_parts.is_some() {
-                    let name_parts = name_parts.unwrap();
-                    name = format!("{}{}", &name_parts[1], &name_parts[2]);
-                }
-            }
-            Input {
-                name,
-                display_name: input.display_name
-            }
-        }).collect();
+            let display_name = input.text().trim();
+            Ok(Input {
+                id: input.name().parse()?,
+                display_name: if display_name.is_empty() { None } else { Some(display_name.to_owned()) }
+            })
+        }).collect::<Result<_>>()?;
 
     Ok(SystemConfig {
         inputs,
-        model_name,
+        model_name: config.find_text("Model_Name").map(|name| name.trim().to_owned()),
+        system_id: config.find_text("System_ID").map(str::trim).filter(|id| !id.is_empty()).map(str::to_owned),
+        firmware_version: config.find_text("Version").and_then(|version| version.trim().parse().ok()),
         available_zones,
-        available_features
+        features
     })
 }
 
@@ -170,9 +104,9 @@ mod tests {
     use super::*;
 
     impl Input {
-        fn new(name: &'static str, display_name: Option<&'static str>) -> Input {
+        fn new(id: InputId, display_name: Option<&'static str>) -> Input {
             Input {
-                name: String::from(name),
+                id,
                 display_name: display_name.map(String::from)
             }
         }
@@ -183,33 +117,63 @@ mod tests {
         let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><System><Config><Model_Name>RX-V473</Model_Name><System_ID>05852093</System_ID><Version>1.14/1.04</Version><Feature_Existence><Main_Zone>1</Main_Zone><Zone_2>0</Zone_2><Zone_3>0</Zone_3><Zone_4>0</Zone_4><Tuner>1</Tuner><HD_Radio>0</HD_Radio><Rhapsody>0</Rhapsody><SIRIUS_IR>0</SIRIUS_IR><Pandora>0</Pandora><SERVER>1</SERVER><NET_RADIO>1</NET_RADIO><USB>1</USB><iPod_USB>1</iPod_USB><AirPlay>1</AirPlay></Feature_Existence><Name><Input><HDMI_1>  Chrome </HDMI_1><HDMI_2>Raspberry</HDMI_2><HDMI_3>   PC    </HDMI_3><HDMI_4>  Game   </HDMI_4><AV_1>         </AV_1><AV_2>   PC    </AV_2><AV_3>   TV    </AV_3><AV_4>         </AV_4><AV_5>   Wii   </AV_5><AV_6>Turntable</AV_6><V_AUX>  V-AUX  </V_AUX><USB>   USB   </USB></Input></Name></Config></System></YAMAHA_AV>");
         assert_eq!(parse_system_config(input).unwrap(), SystemConfig {
             inputs: vec![
-                Input::new("HDMI1", Some("Chrome")),
-                Input::new("HDMI2", Some("Raspberry")),
-                Input::new("HDMI3", Some("PC")),
-                Input::new("HDMI4", Some("Game")),
-                Input::new("AV1", None),
-                Input::new("AV2", Some("PC")),
-                Input::new("AV3", Some("TV")),
-                Input::new("AV4", None),
-                Input::new("AV5", Some("Wii")),
-                Input::new("AV6", Some("Turntable")),
-                Input::new("V-AUX", Some("V-AUX")),
-                Input::new("USB", Some("USB"))
+                Input::new(InputId::Hdmi(1), Some("Chrome")),
+                Input::new(InputId::Hdmi(2), Some("Raspberry")),
+                Input::new(InputId::Hdmi(3), Some("PC")),
+                Input::new(InputId::Hdmi(4), Some("Game")),
+                Input::new(InputId::Av(1), None),
+                Input::new(InputId::Av(2), Some("PC")),
+                Input::new(InputId::Av(3), Some("TV")),
+                Input::new(InputId::Av(4), None),
+                Input::new(InputId::Av(5), Some("Wii")),
+                Input::new(InputId::Av(6), Some("Turntable")),
+                Input::new(InputId::VAux, Some("V-AUX")),
+                Input::new(InputId::Usb, Some("USB"))
             ],
             model_name: Some(String::from("RX-V473")),
+            system_id: Some(String::from("05852093")),
+            firmware_version: Some("1.14/1.04".parse().unwrap()),
             available_zones: vec![String::from("Main_Zone")],
-            available_features: SystemConfigAvailableFeatures {
-                tuner: true,
-                hd_radio: false,
-                rhapsody: false,
-                sirius_ir: false,
-                pandora: false,
-                server: true,
-                net_radio: true,
-                usb: true,
-                ipod: true,
-                air_play: true
+            features: {
+                let mut features = Features::default();
+                for (tag, exists) in &[("Main_Zone", true), ("Zone_2", false), ("Zone_3", false), ("Zone_4", false), ("Tuner", true), ("HD_Radio", false), ("Rhapsody", false), ("SIRIUS_IR", false), ("Pandora", false), ("SERVER", true), ("NET_RADIO", true), ("USB", true), ("iPod_USB", true), ("AirPlay", true)] {
+                    features.insert(Feature::from_tag(tag), *exists);
+                }
+                features
             }
         });
     }
+
+    #[test]
+    fn parse_system_config_should_keep_unknown_features() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><System><Config><Model_Name>RX-V681</Model_Name><Feature_Existence><Main_Zone>1</Main_Zone><Zone_B>1</Zone_B><Tuner>1</Tuner><Bluetooth>1</Bluetooth><MusicCast_Link>1</MusicCast_Link><Deezer>0</Deezer></Feature_Existence></Config></System></YAMAHA_AV>");
+        let config = parse_system_config(input).unwrap();
+        assert!(config.features.tuner());
+        assert!(config.features.bluetooth());
+        assert!(config.features.has(&Feature::ZoneB));
+        assert!(config.features.has(&Feature::MusicCastLink));
+        assert_eq!(config.features.as_map().get(&Feature::Unknown("Deezer".to_owned())), Some(&false));
+        assert!(!config.features.air_play());
+        assert_eq!(config.available_zones, vec!["Main_Zone"]);
+    }
+
+    #[test]
+    fn find_input_should_match_display_names() {
+        let inputs = vec![
+            Input::new(InputId::Hdmi(1), Some("Chrome")),
+            Input::new(InputId::Hdmi(3), Some("PC")),
+            Input::new(InputId::Av(1), None),
+            Input::new(InputId::Av(2), Some("PC Games")),
+            Input::new(InputId::Av(5), Some("Wii")),
+            Input::new(InputId::Av(6), Some("Turntable"))
+        ];
+        assert_eq!(find_input(&inputs, " turntable ").unwrap().id, InputId::Av(6));
+        assert_eq!(find_input(&inputs, "Turn").unwrap().id, InputId::Av(6));
+        assert_eq!(find_input(&inputs, "pc").unwrap().id, InputId::Hdmi(3));
+        match find_input(&inputs, "p") {
+            Err(Error::AmbiguousInput(candidates)) => assert_eq!(candidates.len(), 2),
+            other => panic!("Expected AmbiguousInput, got {:?}", other)
+        }
+        assert!(find_input(&inputs, "Kodi").is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+use crate::error::{Error, Result};
+use super::volume::Volume;
+
+/// Lowest Bass/Treble Level accepted by the Receiver
+pub const MIN_TONE_DB: f32 = -6.0;
+/// Highest Bass/Treble Level accepted by the Receiver
+pub const MAX_TONE_DB: f32 = 6.0;
+
+/// Bass and Treble Levels in dB
+#[derive(PartialEq, Debug, Copy, Clone, Default)]
+pub struct Tone {
+    pub bass: f32,
+    pub treble: f32
+}
+
+/// Round `db` to the 0.5 dB steps of the Receiver, rejecting Levels outside of the Tone Range
+pub(crate) fn tone_level(db: f32) -> Result<Volume> {
+    if !(MIN_TONE_DB..=MAX_TONE_DB).contains(&db) {
+        return Err(Error::InvalidArgument(format!("Tone level {} dB is outside of {} dB to {} dB", db, MIN_TONE_DB, MAX_TONE_DB)));
+    }
+    Ok(Volume::from_db(db))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tone_level_should_check_range() {
+        assert_eq!(tone_level(-2.4).unwrap().db(), -2.5);
+        assert_eq!(tone_level(6.0).unwrap().db(), 6.0);
+        assert!(tone_level(6.5).is_err());
+        assert!(tone_level(f32::NAN).is_err());
+    }
+}
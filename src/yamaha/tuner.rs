@@ -0,0 +1,567 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::error::{Error, Result};
+use super::client::AsyncYamahaAvr;
+use super::command::{Command, Value};
+use super::element::Element;
+use super::features::Feature;
+
+/// Number of Presets the Tuner can store, DAB Presets are stored separately
+pub const MAX_TUNER_PRESETS: u8 = 40;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub enum Band {
+    Fm,
+    Am,
+    /// Digital Radio, Services are selected with `select_dab_service` instead of a Frequency
+    Dab
+}
+
+impl Band {
+    /// The Tag used by the Receiver, e.g. `FM`
+    pub fn tag(self) -> &'static str {
+        match self {
+            Band::Fm => "FM",
+            Band::Am => "AM",
+            Band::Dab => "DAB"
+        }
+    }
+}
+
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+impl FromStr for Band {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Band> {
+        match s.trim().to_uppercase().as_str() {
+            "FM" => Ok(Band::Fm),
+            "AM" => Ok(Band::Am),
+            "DAB" => Ok(Band::Dab),
+            _ => Err(Error::InvalidArgument(format!("Invalid band {}", s)))
+        }
+    }
+}
+
+/// A Tuner Frequency with its Band
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub struct Frequency {
+    band: Band,
+    khz: u32
+}
+
+impl Frequency {
+    /// A FM Frequency in MHz, rounded to 10 kHz
+    ///
+    /// Values outside of the u32 Range saturate, `validate` rejects them.
+    pub fn fm(mhz: f32) -> Frequency {
+        Frequency {
+            band: Band::Fm,
+            khz: ((mhz * 100.0).round() as u32).saturating_mul(10)
+        }
+    }
+
+    pub fn am(khz: u32) -> Frequency {
+        Frequency {
+            band: Band::Am,
+            khz
+        }
+    }
+
+    pub fn band(self) -> Band {
+        self.band
+    }
+
+    pub fn khz(self) -> u32 {
+        self.khz
+    }
+
+    pub fn mhz(self) -> f32 {
+        self.khz as f32 / 1000.0
+    }
+
+    /// Check the Frequency against the Range and Steps of its Band
+    ///
+    /// FM covers 76.00 to 108.00 MHz in 50 kHz Steps, AM covers 522 to 1710 kHz in 9 kHz (Europe, Asia)
+    /// or 10 kHz (Americas) Steps.
+    pub fn validate(self) -> Result<Frequency> {
+        let valid = match self.band {
+            Band::Fm => (76_000..=108_000).contains(&self.khz) && self.khz.is_multiple_of(50),
+            Band::Am => (522..=1710).contains(&self.khz) && ((self.khz - 522).is_multiple_of(9) || self.khz.is_multiple_of(10)),
+            Band::Dab => false
+        };
+        if valid {
+            Ok(self)
+        }else {
+            Err(Error::InvalidArgument(format!("Invalid {} frequency {}", self.band, self)))
+        }
+    }
+
+    /// Decode a `Val`/`Exp`/`Unit` Triple, None when no `Val` is set
+    pub(crate) fn from_level(band: Band, level: &Element) -> Result<Option<Frequency>> {
+        let val: u32 = match level.find_parsed("Val")? {
+            Some(val) => val,
+            None => return Ok(None)
+        };
+        let exp = level.find_parsed("Exp")?.unwrap_or(0);
+        let divisor = 10u32.checked_pow(exp)
+            .ok_or_else(|| Error::UnexpectedResponse(format!("Invalid frequency exponent {}", exp)))?;
+        let khz = match level.find_text("Unit").map(str::trim) {
+            Some("MHz") => val.checked_mul(1000)
+                .ok_or_else(|| Error::UnexpectedResponse(format!("Invalid frequency {} MHz", val)))? / divisor,
+            Some("kHz") => val / divisor,
+            unit => return Err(Error::UnexpectedResponse(format!("Unsupported frequency unit {:?}", unit)))
+        };
+        Ok(Some(Frequency { band, khz }))
+    }
+
+    /// The `Val`/`Exp`/`Unit` Elements for a PUT Command
+    pub(crate) fn to_value(self) -> Value {
+        match self.band {
+            Band::Fm => Value::Level {
+                val: (self.khz / 10).to_string(),
+                exp: Some(2),
+                unit: Some("MHz".to_owned())
+            },
+            // `validate` rejects DAB Frequencies, which leaves AM
+            _ => Value::Level {
+                val: self.khz.to_string(),
+                exp: Some(0),
+                unit: Some("kHz".to_owned())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.band {
+            Band::Fm => write!(f, "{:.2} MHz", self.mhz()),
+            Band::Am => write!(f, "{} kHz", self.khz),
+            Band::Dab => write!(f, "{:.3} MHz", self.mhz())
+        }
+    }
+}
+
+/// Parses `101.1`, `101.1 MHz`, `1080` or `1080 kHz`, Values below 200 without Unit are FM Frequencies in MHz
+impl FromStr for Frequency {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Frequency> {
+        let invalid = || Error::InvalidArgument(format!("Invalid frequency {}", s));
+        let value = s.trim().to_lowercase();
+        let (value, unit) = if let Some(value) = value.strip_suffix("mhz") {
+            (value.trim().to_owned(), Some(Band::Fm))
+        }else if let Some(value) = value.strip_suffix("khz") {
+            (value.trim().to_owned(), Some(Band::Am))
+        }else {
+            (value, None)
+        };
+        let number: f32 = value.parse().map_err(|_| invalid())?;
+        if !number.is_finite() || number < 0.0 {
+            return Err(invalid());
+        }
+        match unit {
+            Some(Band::Fm) => Ok(Frequency::fm(number)),
+            None if number < 200.0 => Ok(Frequency::fm(number)),
+            _ => value.parse().map(Frequency::am).map_err(|_| invalid())
+        }
+    }
+}
+
+/// Radio Data System Information of the current FM Station
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Rds {
+    /// The Station Name, e.g. `WDR 2`
+    pub program_service: Option<String>,
+    /// The Genre, e.g. `POP M`
+    pub program_type: Option<String>,
+    pub radio_text: Option<String>
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TunerStatus {
+    pub band: Option<Band>,
+    pub frequency: Option<Frequency>,
+    /// The selected Preset, None when tuned manually
+    pub preset: Option<u8>,
+    pub tuned: bool,
+    pub stereo: bool,
+    /// None when no RDS Data is received
+    pub rds: Option<Rds>,
+    /// None unless the DAB Band is selected
+    pub dab: Option<DabInfo>
+}
+
+/// Metadata of the current DAB Service
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct DabInfo {
+    /// The Multiplex carrying the Service, e.g. `DR Deutschland`
+    pub ensemble_label: Option<String>,
+    /// The Station Name, e.g. `DLF Kultur`
+    pub service_label: Option<String>,
+    /// Dynamic Label Segment, the Text sent along with the Audio
+    pub dls: Option<String>,
+    /// Signal Quality from 0 to 100
+    pub signal_quality: Option<u8>,
+    /// Audio Bit Rate in kbit/s
+    pub bit_rate: Option<u32>
+}
+
+/// A DAB Service as listed by the Receiver
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct DabService {
+    /// The Number used for `select_dab_service`, starting at 1
+    pub number: u16,
+    /// The Service Label, e.g. `DLF Kultur`
+    pub label: String
+}
+
+/// A stored Tuner Preset
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct TunerPreset {
+    /// The Number used for `recall_preset`, starting at 1
+    pub number: u8,
+    /// The Title shown by the Receiver, e.g. `FM 101.10MHz`
+    pub title: String
+}
+
+pub fn parse_tuner_status(xml: String) -> Result<TunerStatus> {
+    let root = Element::parse(&xml)?;
+    let info = root.find("Tuner/Play_Info")
+        .ok_or_else(|| Error::UnexpectedResponse("Missing Tuner/Play_Info".to_owned()))?;
+    let band = info.find_text("Tuning/Band").and_then(|band| band.parse().ok());
+    let frequency = match (band, info.find("Tuning/Freq/Current")) {
+        (Some(band), Some(level)) => Frequency::from_level(band, level)?,
+        _ => None
+    };
+    Ok(TunerStatus {
+        band,
+        frequency,
+        preset: info.find_text("Preset/Preset_Sel").and_then(|preset| preset.trim().parse().ok()),
+        tuned: info.find_text("Signal_Info/Tuned").map(str::trim) == Some("Assert"),
+        stereo: info.find_text("Signal_Info/Stereo").map(str::trim) == Some("Assert"),
+        rds: info.find("Meta_Info").map(parse_rds).filter(|rds| *rds != Rds::default()),
+        dab: if band == Some(Band::Dab) { Some(parse_dab_info(info)?) } else { None }
+    })
+}
+
+fn non_empty(text: Option<&str>) -> Option<String> {
+    text.map(str::trim).filter(|text| !text.is_empty()).map(str::to_owned)
+}
+
+fn parse_dab_info(info: &Element) -> Result<DabInfo> {
+    Ok(DabInfo {
+        ensemble_label: non_empty(info.find_text("Meta_Info/DAB/Ensemble_Label")),
+        service_label: non_empty(info.find_text("Meta_Info/DAB/Service_Label")),
+        dls: non_empty(info.find_text("Meta_Info/DAB/DLS")),
+        signal_quality: info.find_parsed("Signal_Info/DAB/Quality")?,
+        bit_rate: info.find_parsed("Signal_Info/DAB/Bit_Rate")?
+    })
+}
+
+fn parse_rds(meta: &Element) -> Rds {
+    let text = |path: &str| non_empty(meta.find_text(path));
+    let radio_text = match (text("Radio_Text_A"), text("Radio_Text_B")) {
+        (Some(a), Some(b)) => Some(format!("{} {}", a, b)),
+        (a, b) => a.or(b)
+    };
+    Rds {
+        program_service: text("Program_Service"),
+        program_type: text("Program_Type"),
+        radio_text
+    }
+}
+
+/// Parses the Presets listed below `path`, e.g. `Tuner/Play_Control/Preset/Preset_Sel_Item`
+pub fn parse_tuner_presets(xml: String, path: &str) -> Result<Vec<TunerPreset>> {
+    let items = parse_items(xml, path)?;
+    Ok(items.into_iter().map(|(number, title)| TunerPreset { number, title }).collect())
+}
+
+pub fn parse_dab_services(xml: String) -> Result<Vec<DabService>> {
+    let items = parse_items(xml, "Tuner/Play_Control/DAB/Service_Sel_Item")?;
+    Ok(items.into_iter().map(|(number, label)| DabService { number, label }).collect())
+}
+
+/// Numbered `Item_n` Elements with a `Title`, Items without Title are skipped
+fn parse_items<T: FromStr>(xml: String, path: &str) -> Result<Vec<(T, String)>> {
+    let root = Element::parse(&xml)?;
+    let items = root.find(path)
+        .ok_or_else(|| Error::UnexpectedResponse(format!("Missing {}", path)))?;
+    let mut parsed = Vec::new();
+    for item in items.children() {
+        let number = item.name().strip_prefix("Item_")
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| Error::UnexpectedResponse(format!("Invalid item {}", item.name())))?;
+        let title = item.find_text("Title").unwrap_or_default().trim();
+        if !title.is_empty() {
+            parsed.push((number, title.to_owned()));
+        }
+    }
+    Ok(parsed)
+}
+
+fn check_preset(preset: u8) -> Result<()> {
+    if !(1..=MAX_TUNER_PRESETS).contains(&preset) {
+        return Err(Error::InvalidArgument(format!("Invalid preset {}", preset)));
+    }
+    Ok(())
+}
+
+/// Controls the FM/AM Tuner of a Receiver, including DAB on Models reporting `Feature::Dab`
+///
+/// Obtained via `AsyncYamahaAvr::tuner`.
+pub struct AsyncTuner<'a> {
+    avr: &'a AsyncYamahaAvr
+}
+
+impl<'a> AsyncTuner<'a> {
+    pub(crate) fn new(avr: &'a AsyncYamahaAvr) -> AsyncTuner<'a> {
+        AsyncTuner {
+            avr
+        }
+    }
+
+    /// Query all Values below `path`, relative to the Tuner
+    async fn get(&self, path: &[&str]) -> Result<String> {
+        self.avr.exec(Command::get(&self.path(path))).await
+    }
+
+    /// Set the Value at `path`, relative to the Tuner
+    async fn put<V: Into<Value>>(&self, path: &[&str], value: V) -> Result<String> {
+        self.avr.exec(Command::put(&self.path(path), value)).await
+    }
+
+    fn path<'p>(&self, path: &[&'p str]) -> Vec<&'p str> {
+        let mut full = vec!["Tuner"];
+        full.extend_from_slice(path);
+        full
+    }
+
+    pub async fn get_status(&self) -> Result<TunerStatus> {
+        let res = self.get(&["Play_Info"]).await?;
+        parse_tuner_status(res)
+    }
+
+    pub async fn get_band(&self) -> Result<Band> {
+        let status = self.get_status().await?;
+        status.band.ok_or_else(|| Error::UnexpectedResponse("Missing Tuning/Band".to_owned()))
+    }
+
+    /// Fails with `Error::Unsupported` when switching to DAB on a Receiver without DAB
+    pub async fn set_band(&self, band: Band) -> Result<()> {
+        if band == Band::Dab {
+            self.avr.require(Feature::Dab).await?;
+        }
+        self.put(&["Play_Control", "Tuning", "Band"], band.tag()).await?;
+        Ok(())
+    }
+
+    pub async fn get_frequency(&self) -> Result<Frequency> {
+        let status = self.get_status().await?;
+        status.frequency.ok_or_else(|| Error::UnexpectedResponse("Missing Tuning/Freq".to_owned()))
+    }
+
+    /// Tune to the Frequency, switching to its Band
+    ///
+    /// Fails with `Error::InvalidArgument` when the Frequency is outside of its Band or not on a Step,
+    /// DAB Frequencies can not be set.
+    pub async fn set_frequency(&self, frequency: Frequency) -> Result<()> {
+        let frequency = frequency.validate()?;
+        self.put(&["Play_Control", "Tuning", "Freq", frequency.band().tag()], frequency.to_value()).await?;
+        Ok(())
+    }
+
+    /// Search the next Station above the current Frequency
+    pub async fn seek_up(&self) -> Result<()> {
+        self.seek("Auto Up").await
+    }
+
+    /// Search the next Station below the current Frequency
+    pub async fn seek_down(&self) -> Result<()> {
+        self.seek("Auto Down").await
+    }
+
+    async fn seek(&self, direction: &str) -> Result<()> {
+        let band = self.get_band().await?;
+        let value = Value::Level { val: direction.to_owned(), exp: None, unit: None };
+        self.put(&["Play_Control", "Tuning", "Freq", band.tag()], value).await?;
+        Ok(())
+    }
+
+    /// The stored Presets, empty Presets are skipped
+    pub async fn get_presets(&self) -> Result<Vec<TunerPreset>> {
+        let res = self.get(&["Play_Control", "Preset", "Preset_Sel_Item"]).await?;
+        parse_tuner_presets(res, "Tuner/Play_Control/Preset/Preset_Sel_Item")
+    }
+
+    /// Tune to a stored Preset, starting at 1
+    pub async fn recall_preset(&self, preset: u8) -> Result<()> {
+        check_preset(preset)?;
+        self.put(&["Play_Control", "Preset", "Preset_Sel"], preset.to_string()).await?;
+        Ok(())
+    }
+
+    /// Store the current Frequency as Preset, starting at 1
+    pub async fn store_preset(&self, preset: u8) -> Result<()> {
+        check_preset(preset)?;
+        self.put(&["Play_Control", "Preset", "Memory"], preset.to_string()).await?;
+        Ok(())
+    }
+
+    /// The DAB Services found by the last Scan
+    pub async fn get_dab_services(&self) -> Result<Vec<DabService>> {
+        self.avr.require(Feature::Dab).await?;
+        let res = self.get(&["Play_Control", "DAB", "Service_Sel_Item"]).await?;
+        parse_dab_services(res)
+    }
+
+    /// Tune to a DAB Service from `get_dab_services`, starting at 1
+    pub async fn select_dab_service(&self, service: u16) -> Result<()> {
+        self.avr.require(Feature::Dab).await?;
+        if service == 0 {
+            return Err(Error::InvalidArgument(format!("Invalid DAB service {}", service)));
+        }
+        self.put(&["Play_Control", "DAB", "Service_Sel"], service.to_string()).await?;
+        Ok(())
+    }
+
+    /// The stored DAB Presets, empty Presets are skipped
+    pub async fn get_dab_presets(&self) -> Result<Vec<TunerPreset>> {
+        self.avr.require(Feature::Dab).await?;
+        let res = self.get(&["Play_Control", "Preset", "DAB", "Preset_Sel_Item"]).await?;
+        parse_tuner_presets(res, "Tuner/Play_Control/Preset/DAB/Preset_Sel_Item")
+    }
+
+    /// Tune to a stored DAB Preset, starting at 1
+    pub async fn recall_dab_preset(&self, preset: u8) -> Result<()> {
+        self.avr.require(Feature::Dab).await?;
+        check_preset(preset)?;
+        self.put(&["Play_Control", "Preset", "DAB", "Preset_Sel"], preset.to_string()).await?;
+        Ok(())
+    }
+
+    /// Store the current DAB Service as Preset, starting at 1
+    pub async fn store_dab_preset(&self, preset: u8) -> Result<()> {
+        self.avr.require(Feature::Dab).await?;
+        check_preset(preset)?;
+        self.put(&["Play_Control", "Preset", "DAB", "Memory"], preset.to_string()).await?;
+        Ok(())
+    }
+
+    /// The Metadata of the current DAB Service, None when the DAB Band is not selected
+    pub async fn get_dab_info(&self) -> Result<Option<DabInfo>> {
+        let status = self.get_status().await?;
+        Ok(status.dab)
+    }
+
+    /// The RDS Information of the current Station, None when none is received
+    pub async fn get_rds(&self) -> Result<Option<Rds>> {
+        let status = self.get_status().await?;
+        Ok(status.rds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frequency_should_parse_bands() {
+        assert_eq!("101.1".parse::<Frequency>().unwrap(), Frequency::fm(101.1));
+        assert_eq!("101.10 MHz".parse::<Frequency>().unwrap().khz(), 101_100);
+        assert_eq!("1080".parse::<Frequency>().unwrap(), Frequency::am(1080));
+        assert_eq!("1080kHz".parse::<Frequency>().unwrap().band(), Band::Am);
+        assert!("loud".parse::<Frequency>().is_err());
+        assert!("inf MHz".parse::<Frequency>().is_err());
+        assert!("-101.1".parse::<Frequency>().is_err());
+        assert!("100000000 MHz".parse::<Frequency>().unwrap().validate().is_err());
+        assert_eq!(Frequency::fm(101.1).to_string(), "101.10 MHz");
+    }
+
+    #[test]
+    fn frequency_should_validate_band_steps() {
+        assert!(Frequency::fm(87.5).validate().is_ok());
+        assert!(Frequency::fm(101.15).validate().is_ok());
+        assert!(Frequency::fm(101.12).validate().is_err());
+        assert!(Frequency::fm(120.0).validate().is_err());
+        assert!(Frequency::am(531).validate().is_ok());
+        assert!(Frequency::am(1080).validate().is_ok());
+        assert!(Frequency::am(1085).validate().is_err());
+        assert!(Frequency::am(200).validate().is_err());
+        assert!(Frequency { band: Band::Dab, khz: 178_352 }.validate().is_err());
+    }
+
+    #[test]
+    fn parse_tuner_status_should_parse_rds() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Tuner><Play_Info><Feature_Availability>Ready</Feature_Availability><Search_Mode>Preset</Search_Mode><Preset><Preset_Sel>3</Preset_Sel></Preset><Tuning><Band>FM</Band><Freq><Current><Val>10110</Val><Exp>2</Exp><Unit>MHz</Unit></Current></Freq></Tuning><FM_Mode>Auto</FM_Mode><Signal_Info><Tuned>Assert</Tuned><Stereo>Negate</Stereo></Signal_Info><Meta_Info><Program_Type>POP M</Program_Type><Program_Service>WDR 2   </Program_Service><Radio_Text_A>Der Sender mit</Radio_Text_A><Radio_Text_B>mehr Musik</Radio_Text_B><Clock_Time></Clock_Time></Meta_Info></Play_Info></Tuner></YAMAHA_AV>");
+        assert_eq!(parse_tuner_status(input).unwrap(), TunerStatus {
+            band: Some(Band::Fm),
+            frequency: Some(Frequency::fm(101.1)),
+            preset: Some(3),
+            tuned: true,
+            stereo: false,
+            rds: Some(Rds {
+                program_service: Some("WDR 2".to_owned()),
+                program_type: Some("POP M".to_owned()),
+                radio_text: Some("Der Sender mit mehr Musik".to_owned())
+            }),
+            dab: None
+        });
+    }
+
+    #[test]
+    fn parse_tuner_status_should_parse_am() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Tuner><Play_Info><Preset><Preset_Sel>No Preset</Preset_Sel></Preset><Tuning><Band>AM</Band><Freq><Current><Val>1080</Val><Exp>0</Exp><Unit>kHz</Unit></Current></Freq></Tuning><Signal_Info><Tuned>Negate</Tuned><Stereo>Negate</Stereo></Signal_Info><Meta_Info><Program_Type></Program_Type><Program_Service></Program_Service><Radio_Text_A></Radio_Text_A><Radio_Text_B></Radio_Text_B></Meta_Info></Play_Info></Tuner></YAMAHA_AV>");
+        let status = parse_tuner_status(input).unwrap();
+        assert_eq!(status.frequency, Some(Frequency::am(1080)));
+        assert_eq!(status.preset, None);
+        assert_eq!(status.rds, None);
+    }
+
+    #[test]
+    fn parse_tuner_status_should_reject_invalid_exponents() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Tuner><Play_Info><Tuning><Band>FM</Band><Freq><Current><Val>8750</Val><Exp>10</Exp><Unit>MHz</Unit></Current></Freq></Tuning></Play_Info></Tuner></YAMAHA_AV>");
+        assert!(parse_tuner_status(input).is_err());
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Tuner><Play_Info><Tuning><Band>FM</Band><Freq><Current><Val>4294968</Val><Exp>2</Exp><Unit>MHz</Unit></Current></Freq></Tuning></Play_Info></Tuner></YAMAHA_AV>");
+        assert!(parse_tuner_status(input).is_err());
+    }
+
+    #[test]
+    fn parse_tuner_presets_should_skip_empty_items() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Tuner><Play_Control><Preset><Preset_Sel_Item><Item_1><Param>1</Param><RW>RW</RW><Title>FM 101.10MHz</Title></Item_1><Item_2><Param>2</Param><RW>RW</RW><Title></Title></Item_2><Item_3><Param>3</Param><RW>RW</RW><Title>AM 1080kHz</Title></Item_3></Preset_Sel_Item></Preset></Play_Control></Tuner></YAMAHA_AV>");
+        assert_eq!(parse_tuner_presets(input, "Tuner/Play_Control/Preset/Preset_Sel_Item").unwrap(), vec![
+            TunerPreset { number: 1, title: "FM 101.10MHz".to_owned() },
+            TunerPreset { number: 3, title: "AM 1080kHz".to_owned() }
+        ]);
+    }
+
+    #[test]
+    fn parse_tuner_status_should_parse_dab_metadata() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Tuner><Play_Info><Preset><Preset_Sel>No Preset</Preset_Sel></Preset><Tuning><Band>DAB</Band></Tuning><Signal_Info><Tuned>Assert</Tuned><Stereo>Assert</Stereo><DAB><Quality>82</Quality><Bit_Rate>96</Bit_Rate></DAB></Signal_Info><Meta_Info><Program_Type></Program_Type><Program_Service></Program_Service><Radio_Text_A></Radio_Text_A><Radio_Text_B></Radio_Text_B><DAB><Ensemble_Label>DR Deutschland  </Ensemble_Label><Service_Label>DLF Kultur</Service_Label><DLS>Tonart - Musik</DLS></DAB></Meta_Info></Play_Info></Tuner></YAMAHA_AV>");
+        let status = parse_tuner_status(input).unwrap();
+        assert_eq!(status.band, Some(Band::Dab));
+        assert_eq!(status.frequency, None);
+        assert_eq!(status.dab, Some(DabInfo {
+            ensemble_label: Some("DR Deutschland".to_owned()),
+            service_label: Some("DLF Kultur".to_owned()),
+            dls: Some("Tonart - Musik".to_owned()),
+            signal_quality: Some(82),
+            bit_rate: Some(96)
+        }));
+    }
+
+    #[test]
+    fn parse_dab_services_should_parse_items() {
+        let input = String::from("<YAMAHA_AV rsp=\"GET\" RC=\"0\"><Tuner><Play_Control><DAB><Service_Sel_Item><Item_1><Param>1</Param><Title>DLF Kultur</Title></Item_1><Item_2><Param>2</Param><Title>Deutschlandfunk</Title></Item_2></Service_Sel_Item></DAB></Play_Control></Tuner></YAMAHA_AV>");
+        assert_eq!(parse_dab_services(input).unwrap(), vec![
+            DabService { number: 1, label: "DLF Kultur".to_owned() },
+            DabService { number: 2, label: "Deutschlandfunk".to_owned() }
+        ]);
+    }
+}
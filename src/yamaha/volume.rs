@@ -0,0 +1,338 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use crate::error::{Error, Result};
+use super::command::Value;
+use super::element::Element;
+
+/// Lowest Volume supported by most RX Models
+pub const MIN_VOLUME_DB: f32 = -80.5;
+/// Highest Volume supported by most RX Models
+pub const MAX_VOLUME_DB: f32 = 16.5;
+
+/// A Volume Level in dB, always a multiple of the Receivers 0.5 dB step
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Default, Hash)]
+pub struct Volume {
+    /// The Level in tenths of a dB, as sent with `<Exp>1</Exp>`
+    tenths: i32
+}
+
+impl Volume {
+    /// Create a Volume from a dB Value, rounding to the nearest 0.5 dB step
+    ///
+    /// Values too large to represent saturate, NaN is treated as `MIN_VOLUME_DB`.
+    /// Use `try_from_db` to reject non finite Values instead.
+    pub fn from_db(db: f32) -> Volume {
+        let db = if db.is_nan() { MIN_VOLUME_DB } else { db };
+        Volume {
+            tenths: ((db * 2.0).round() as i32).saturating_mul(5)
+        }
+    }
+
+    /// Like `from_db`, but fails with `Error::InvalidArgument` for NaN and infinite Values
+    pub fn try_from_db(db: f32) -> Result<Volume> {
+        if !db.is_finite() {
+            return Err(Error::InvalidArgument(format!("Invalid volume {}", db)));
+        }
+        Ok(Volume::from_db(db))
+    }
+
+    /// Decode the `Val`/`Exp`/`Unit` Triple of a Response
+    pub(crate) fn from_raw(val: i32, exp: u32, unit: &str) -> Result<Volume> {
+        if unit != "dB" {
+            return Err(Error::UnexpectedResponse(format!("Unsupported volume unit {}", unit)));
+        }
+        let db = val as f32 / 10f32.powi(exp as i32);
+        Ok(Volume::from_db(db))
+    }
+
+    /// Decode a Level Element with `Val`/`Exp`/`Unit` Children, None when no `Val` is set
+    pub(crate) fn from_level(level: &Element) -> Result<Option<Volume>> {
+        let val = match level.find_parsed("Val")? {
+            Some(val) => val,
+            None => return Ok(None)
+        };
+        let exp = level.find_parsed("Exp")?.unwrap_or(0);
+        let unit = level.find_text("Unit").map(str::trim).filter(|unit| !unit.is_empty()).unwrap_or("dB");
+        Volume::from_raw(val, exp, unit).map(Some)
+    }
+
+    pub fn db(self) -> f32 {
+        self.tenths as f32 / 10.0
+    }
+
+    /// Limit the Volume to the given Range
+    pub fn clamp(self, range: VolumeRange) -> Volume {
+        Ord::clamp(self, range.min, range.max)
+    }
+
+    /// The `Val`/`Exp`/`Unit` Elements for a PUT Command
+    pub(crate) fn to_value(self) -> Value {
+        Value::Level {
+            val: self.tenths.to_string(),
+            exp: Some(1),
+            unit: Some("dB".to_owned())
+        }
+    }
+}
+
+impl fmt::Display for Volume {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.1} dB", self.db())
+    }
+}
+
+/// Parses dB Values with an optional Unit, e.g. `-35.5` or `-35.5 dB`
+impl FromStr for Volume {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Volume> {
+        let db: f32 = s.trim().trim_end_matches("dB").trim().parse()
+            .map_err(|_| Error::InvalidArgument(format!("Invalid volume {}", s)))?;
+        Volume::try_from_db(db)
+    }
+}
+
+/// The relative Steps supported by the Receiver
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum VolumeStep {
+    /// The smallest Step, 0.5 dB on most Models
+    Min,
+    Db1,
+    Db2,
+    Db5
+}
+
+impl VolumeStep {
+    pub fn db(self) -> f32 {
+        match self {
+            VolumeStep::Min => 0.5,
+            VolumeStep::Db1 => 1.0,
+            VolumeStep::Db2 => 2.0,
+            VolumeStep::Db5 => 5.0
+        }
+    }
+
+    /// The `Val` for a PUT Command, e.g. `Up 2 dB`
+    pub(crate) fn value(self, up: bool) -> Value {
+        let direction = if up { "Up" } else { "Down" };
+        let val = match self {
+            VolumeStep::Min => direction.to_owned(),
+            step => format!("{} {} dB", direction, step.db())
+        };
+        Value::Level {
+            val,
+            exp: None,
+            unit: None
+        }
+    }
+
+    /// Split a Change in dB into the fewest Steps, largest first
+    pub(crate) fn decompose(delta_db: f32) -> Vec<VolumeStep> {
+        let mut remaining = Volume::from_db(delta_db.abs()).tenths;
+        let mut steps = Vec::new();
+        for step in &[VolumeStep::Db5, VolumeStep::Db2, VolumeStep::Db1, VolumeStep::Min] {
+            let tenths = (step.db() * 10.0) as i32;
+            while remaining >= tenths {
+                steps.push(*step);
+                remaining -= tenths;
+            }
+        }
+        steps
+    }
+}
+
+/// Cancels a running `fade_volume` from another Task or Thread
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Fades never send more than one Command per this Interval
+pub(crate) const MIN_FADE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The Volumes to set while fading from `from` to `to` and the Delay between them
+pub(crate) fn fade_steps(from: Volume, to: Volume, duration: Duration) -> (Vec<Volume>, Duration) {
+    let half_steps = ((to.tenths - from.tenths) / 5).unsigned_abs();
+    if half_steps == 0 {
+        return (vec![to], Duration::from_secs(0));
+    }
+    let max_steps = u32::try_from(duration.as_millis() / MIN_FADE_INTERVAL.as_millis()).unwrap_or(u32::MAX).max(1);
+    let count = half_steps.min(max_steps);
+    let interval = duration / count;
+    let steps = (1..=count)
+        .map(|i| {
+            let db = from.db() + (to.db() - from.db()) * i as f32 / count as f32;
+            Volume::from_db(db)
+        })
+        .collect();
+    (steps, interval)
+}
+
+/// The Volume Range a Zone accepts, `min` is never above `max`
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct VolumeRange {
+    min: Volume,
+    max: Volume
+}
+
+impl VolumeRange {
+    /// Fails with `Error::InvalidArgument` when `min` is above `max`
+    pub fn new(min: Volume, max: Volume) -> Result<VolumeRange> {
+        if min > max {
+            return Err(Error::InvalidArgument(format!("Invalid volume range {} to {}", min, max)));
+        }
+        Ok(VolumeRange { min, max })
+    }
+
+    pub fn min(self) -> Volume {
+        self.min
+    }
+
+    pub fn max(self) -> Volume {
+        self.max
+    }
+
+    /// Limit a relative Change to the Span of the Range, no Change can move the Volume further than that
+    ///
+    /// Fails with `Error::InvalidArgument` for NaN and infinite Changes
+    pub(crate) fn clamp_delta(self, delta_db: f32) -> Result<f32> {
+        if !delta_db.is_finite() {
+            return Err(Error::InvalidArgument(format!("Invalid volume change {}", delta_db)));
+        }
+        let span = self.max.db() - self.min.db();
+        Ok(delta_db.clamp(-span, span))
+    }
+}
+
+impl Default for VolumeRange {
+    fn default() -> VolumeRange {
+        VolumeRange {
+            min: Volume::from_db(MIN_VOLUME_DB),
+            max: Volume::from_db(MAX_VOLUME_DB)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_db_should_round_to_half_db_steps() {
+        assert_eq!(Volume::from_db(-35.4).db(), -35.5);
+        assert_eq!(Volume::from_db(-35.2).db(), -35.0);
+        assert_eq!(Volume::from_db(0.0).db(), 0.0);
+    }
+
+    #[test]
+    fn from_db_should_not_overflow() {
+        assert_eq!(Volume::from_db(1e9).clamp(VolumeRange::default()).db(), MAX_VOLUME_DB);
+        assert_eq!(Volume::from_db(f32::NEG_INFINITY).clamp(VolumeRange::default()).db(), MIN_VOLUME_DB);
+        assert_eq!(Volume::from_db(f32::NAN).db(), MIN_VOLUME_DB);
+    }
+
+    #[test]
+    fn try_from_db_should_reject_non_finite_values() {
+        assert!(Volume::try_from_db(f32::NAN).is_err());
+        assert!(Volume::try_from_db(f32::INFINITY).is_err());
+        assert!(Volume::try_from_db(f32::NEG_INFINITY).is_err());
+        assert!(Volume::try_from_db(1e9).is_ok());
+        assert!("NaN".parse::<Volume>().is_err());
+        assert!("inf".parse::<Volume>().is_err());
+        assert!("-inf dB".parse::<Volume>().is_err());
+    }
+
+    #[test]
+    fn from_raw_should_apply_exponent() {
+        assert_eq!(Volume::from_raw(-300, 1, "dB").unwrap().db(), -30.0);
+        assert_eq!(Volume::from_raw(-30, 0, "dB").unwrap().db(), -30.0);
+        assert!(Volume::from_raw(-300, 1, "%").is_err());
+    }
+
+    #[test]
+    fn clamp_should_limit_to_range() {
+        let range = VolumeRange::default();
+        assert_eq!(VolumeRange::new(range.min(), range.max()).unwrap(), range);
+        assert!(VolumeRange::new(range.max(), range.min()).is_err());
+        assert_eq!(Volume::from_db(30.0).clamp(range).db(), MAX_VOLUME_DB);
+        assert_eq!(Volume::from_db(-100.0).clamp(range).db(), MIN_VOLUME_DB);
+        assert_eq!(Volume::from_db(-20.0).clamp(range).db(), -20.0);
+    }
+
+    #[test]
+    fn to_value_should_use_tenths() {
+        assert_eq!(Volume::from_db(-35.5).to_value(), Value::Level { val: "-355".to_owned(), exp: Some(1), unit: Some("dB".to_owned()) });
+    }
+
+    #[test]
+    fn volume_step_should_format_values() {
+        assert_eq!(VolumeStep::Min.value(true), Value::Level { val: "Up".to_owned(), exp: None, unit: None });
+        assert_eq!(VolumeStep::Db2.value(false), Value::Level { val: "Down 2 dB".to_owned(), exp: None, unit: None });
+    }
+
+    #[test]
+    fn decompose_should_use_largest_steps() {
+        assert_eq!(VolumeStep::decompose(8.5), vec![VolumeStep::Db5, VolumeStep::Db2, VolumeStep::Db1, VolumeStep::Min]);
+        assert_eq!(VolumeStep::decompose(-2.0), vec![VolumeStep::Db2]);
+        assert!(VolumeStep::decompose(0.1).is_empty());
+    }
+
+    #[test]
+    fn clamp_delta_should_limit_to_span() {
+        let range = VolumeRange::default();
+        assert_eq!(range.clamp_delta(1000.0).unwrap(), MAX_VOLUME_DB - MIN_VOLUME_DB);
+        assert_eq!(range.clamp_delta(-1000.0).unwrap(), MIN_VOLUME_DB - MAX_VOLUME_DB);
+        assert_eq!(range.clamp_delta(-2.5).unwrap(), -2.5);
+        assert!(range.clamp_delta(f32::INFINITY).is_err());
+        assert!(range.clamp_delta(f32::NAN).is_err());
+        assert_eq!(VolumeStep::decompose(range.clamp_delta(1000.0).unwrap()).len(), 20);
+    }
+
+    #[test]
+    fn fade_steps_should_reach_target() {
+        let (steps, interval) = fade_steps(Volume::from_db(-30.0), Volume::from_db(-32.0), Duration::from_secs(2));
+        assert_eq!(steps, vec![Volume::from_db(-30.5), Volume::from_db(-31.0), Volume::from_db(-31.5), Volume::from_db(-32.0)]);
+        assert_eq!(interval, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn fade_steps_should_respect_min_interval() {
+        let (steps, interval) = fade_steps(Volume::from_db(-20.0), Volume::from_db(-50.0), Duration::from_secs(1));
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps.last(), Some(&Volume::from_db(-50.0)));
+        assert_eq!(interval, MIN_FADE_INTERVAL);
+    }
+
+    #[test]
+    fn fade_steps_should_handle_long_durations() {
+        for duration in &[Duration::MAX, MIN_FADE_INTERVAL * u32::MAX + MIN_FADE_INTERVAL] {
+            let (steps, _) = fade_steps(Volume::from_db(-20.0), Volume::from_db(-30.0), *duration);
+            assert_eq!(steps.len(), 20);
+        }
+    }
+
+    #[test]
+    fn volume_should_parse_cli_values() {
+        assert_eq!("-35.5".parse::<Volume>().unwrap(), Volume::from_db(-35.5));
+        assert_eq!("-20 dB".parse::<Volume>().unwrap(), Volume::from_db(-20.0));
+        assert!("loud".parse::<Volume>().is_err());
+    }
+}
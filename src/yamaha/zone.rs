@@ -0,0 +1,393 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use chrono::Local;
+use tokio::time;
+use crate::error::{Error, Result};
+use super::client::AsyncYamahaAvr;
+use super::command::{Command, Value};
+use super::input::InputId;
+use super::scene::{self, Scene};
+use super::sleep::SleepTimer;
+use super::system_config;
+use super::sound_program::SoundProgram;
+use super::tone::{self, Tone};
+use super::volume::{self, CancellationToken, Volume, VolumeRange, VolumeStep};
+use super::basic_info::{self, HdmiOutput};
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Zone {
+    Main,
+    Zone2,
+    Zone3,
+    Zone4
+}
+
+impl Zone {
+    /// The Tag used by the Receiver, e.g. `Main_Zone`
+    pub fn tag(self) -> &'static str {
+        match self {
+            Zone::Main => "Main_Zone",
+            Zone::Zone2 => "Zone_2",
+            Zone::Zone3 => "Zone_3",
+            Zone::Zone4 => "Zone_4"
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Zone> {
+        match tag {
+            "Main_Zone" => Some(Zone::Main),
+            "Zone_2" => Some(Zone::Zone2),
+            "Zone_3" => Some(Zone::Zone3),
+            "Zone_4" => Some(Zone::Zone4),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+/// Parses the Receiver Tags (`Zone_2`) as well as short names (`main`, `zone2`, `2`)
+impl FromStr for Zone {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Zone> {
+        if let Some(zone) = Zone::from_tag(s) {
+            return Ok(zone);
+        }
+        match s.to_lowercase().replace('_', "").as_str() {
+            "main" | "mainzone" | "1" => Ok(Zone::Main),
+            "zone2" | "2" => Ok(Zone::Zone2),
+            "zone3" | "3" => Ok(Zone::Zone3),
+            "zone4" | "4" => Ok(Zone::Zone4),
+            _ => Err(Error::InvalidArgument(format!("Invalid zone {}", s)))
+        }
+    }
+}
+
+/// Controls a single Zone of a Receiver
+///
+/// Obtained via `AsyncYamahaAvr::zone` or `AsyncYamahaAvr::main_zone`.
+pub struct AsyncZoneControl<'a> {
+    avr: &'a AsyncYamahaAvr,
+    zone: Zone
+}
+
+impl<'a> AsyncZoneControl<'a> {
+    pub(crate) fn new(avr: &'a AsyncYamahaAvr, zone: Zone) -> AsyncZoneControl<'a> {
+        AsyncZoneControl {
+            avr,
+            zone
+        }
+    }
+
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+
+    /// Query all Values below `path`, relative to this Zone
+    async fn get(&self, path: &[&str]) -> Result<String> {
+        self.avr.exec(Command::get(&self.path(path))).await
+    }
+
+    /// Set the Value at `path`, relative to this Zone
+    async fn put<V: Into<Value>>(&self, path: &[&str], value: V) -> Result<String> {
+        self.avr.exec(Command::put(&self.path(path), value)).await
+    }
+
+    fn path<'p>(&self, path: &[&'p str]) -> Vec<&'p str> {
+        let mut full = vec![self.zone.tag()];
+        full.extend_from_slice(path);
+        full
+    }
+
+    pub async fn get_basic_info(&self) -> Result<basic_info::BasicInfo> {
+        let res = self.get(&["Basic_Status"]).await?;
+        basic_info::parse_basic_info(res)
+    }
+
+    pub async fn get_power(&self) -> Result<bool> {
+        let info = self.get_basic_info().await?;
+        Ok(info.power)
+    }
+
+    pub async fn set_power(&self, value: bool) -> Result<()> {
+        self.put(&["Power_Control", "Power"], if value { "On" } else { "Standby" }).await?;
+        Ok(())
+    }
+
+    pub async fn get_mute(&self) -> Result<bool> {
+        let info = self.get_basic_info().await?;
+        Ok(info.mute)
+    }
+
+    pub async fn set_mute(&self, value: bool) -> Result<()> {
+        self.put(&["Volume", "Mute"], Value::on_off(value)).await?;
+        Ok(())
+    }
+
+    /// Fails with `Error::UnexpectedResponse` when the Zone does not report its Volume,
+    /// which also makes the `volume_limits` checks fail instead of guessing the current Volume
+    pub async fn get_volume(&self) -> Result<Volume> {
+        let info = self.get_basic_info().await?;
+        info.volume.ok_or_else(|| Error::UnexpectedResponse("Missing Volume/Lvl".to_owned()))
+    }
+
+    /// The Range `set_volume` clamps to, see `AsyncYamahaAvr::zone_volume_range`
+    pub fn volume_range(&self) -> VolumeRange {
+        self.avr.zone_volume_range(self.zone)
+    }
+
+    /// Set the Volume, values outside of the Zones `volume_range` are clamped
+    ///
+    /// Fails with `Error::VolumeLimitExceeded` when the Increase is not allowed by the `volume_limits`
+    pub async fn set_volume(&self, value: Volume) -> Result<()> {
+        let value = value.clamp(self.volume_range());
+        let limits = self.avr.volume_limits();
+        if limits.is_active() {
+            let current = self.get_volume().await?;
+            limits.check(current, value, Local::now().time())?;
+        }
+        self.put_volume(value).await
+    }
+
+    async fn put_volume(&self, value: Volume) -> Result<()> {
+        self.put(&["Volume", "Lvl"], value.to_value()).await?;
+        Ok(())
+    }
+
+    pub async fn volume_up(&self, step: VolumeStep) -> Result<()> {
+        self.check_increase(step.db()).await?;
+        self.step_volume(step, true).await
+    }
+
+    pub async fn volume_down(&self, step: VolumeStep) -> Result<()> {
+        self.step_volume(step, false).await
+    }
+
+    async fn step_volume(&self, step: VolumeStep, up: bool) -> Result<()> {
+        self.put(&["Volume", "Lvl"], step.value(up)).await?;
+        Ok(())
+    }
+
+    /// Check a relative Increase against the `volume_limits`, only querying the Volume when Limits are set
+    async fn check_increase(&self, delta_db: f32) -> Result<()> {
+        let limits = self.avr.volume_limits();
+        if limits.is_active() {
+            let current = self.get_volume().await?;
+            let requested = Volume::from_db(current.db() + delta_db);
+            limits.check(current, requested, Local::now().time())?;
+        }
+        Ok(())
+    }
+
+    /// Change the Volume by `delta_db`, rounded to 0.5 dB and sent as the fewest relative Steps
+    ///
+    /// Changes larger than the `volume_range` are limited to it, NaN and infinite Changes are rejected.
+    /// The whole Change counts as a single Step for the `volume_limits`.
+    pub async fn change_volume(&self, delta_db: f32) -> Result<()> {
+        let delta_db = self.volume_range().clamp_delta(delta_db)?;
+        if delta_db > 0.0 {
+            self.check_increase(delta_db).await?;
+        }
+        for step in VolumeStep::decompose(delta_db) {
+            self.step_volume(step, delta_db > 0.0).await?;
+        }
+        Ok(())
+    }
+
+    /// Gradually change the Volume to `target` over `duration`
+    ///
+    /// Stops early when `cancel` is cancelled and returns the Volume which was set last.
+    /// A `target` above the `volume_limits` is rejected before the Fade starts,
+    /// each Step of the Fade has to respect the maximum Step as well.
+    pub async fn fade_volume(&self, target: Volume, duration: Duration, cancel: &CancellationToken) -> Result<Volume> {
+        let target = target.clamp(self.volume_range());
+        let limits = self.avr.volume_limits();
+        let mut current = self.get_volume().await?;
+        if target > current {
+            limits.check_ceiling(target, Local::now().time())?;
+        }
+        let (steps, interval) = volume::fade_steps(current, target, duration);
+        for (i, step) in steps.into_iter().enumerate() {
+            if i > 0 {
+                time::sleep(interval).await;
+            }
+            if cancel.is_cancelled() {
+                break;
+            }
+            limits.check(current, step, Local::now().time())?;
+            self.put_volume(step).await?;
+            current = step;
+        }
+        Ok(current)
+    }
+
+    /// The current DSP Program, None for Zones without DSP
+    pub async fn get_sound_program(&self) -> Result<Option<SoundProgram>> {
+        let info = self.get_basic_info().await?;
+        Ok(info.surround.and_then(|surround| surround.sound_program))
+    }
+
+    /// The DSP Programs of the loaded Unit Description, `SoundProgram::all` when none is loaded
+    pub fn sound_programs(&self) -> Vec<SoundProgram> {
+        self.avr.capabilities()
+            .and_then(|capabilities| capabilities.sound_programs(self.zone))
+            .unwrap_or_else(SoundProgram::all)
+    }
+
+    /// Select a DSP Program, see `sound_programs` for the Programs of the Model
+    ///
+    /// Fails with `Error::InvalidValue` when the Model does not support the Program
+    pub async fn set_sound_program(&self, program: SoundProgram) -> Result<()> {
+        self.put(&["Surround", "Program_Sel", "Current", "Sound_Program"], program.value()).await?;
+        Ok(())
+    }
+
+    /// Bypass the DSP Program
+    pub async fn set_straight(&self, value: bool) -> Result<()> {
+        self.put(&["Surround", "Program_Sel", "Current", "Straight"], Value::on_off(value)).await?;
+        Ok(())
+    }
+
+    /// Enable the Compressed Music Enhancer
+    pub async fn set_enhancer(&self, value: bool) -> Result<()> {
+        self.put(&["Surround", "Program_Sel", "Current", "Enhancer"], Value::on_off(value)).await?;
+        Ok(())
+    }
+
+    /// Enable CINEMA DSP 3D, the Receiver reports it as `Auto` when enabled
+    pub async fn set_3d_cinema_dsp(&self, value: bool) -> Result<()> {
+        self.put(&["Surround", "_3D_Cinema_DSP"], if value { "Auto" } else { "Off" }).await?;
+        Ok(())
+    }
+
+    /// Bass and Treble, None for Zones without Tone Control
+    pub async fn get_tone(&self) -> Result<Option<Tone>> {
+        let info = self.get_basic_info().await?;
+        Ok(info.sound_video.and_then(|sound_video| sound_video.tone))
+    }
+
+    /// Set the Bass Level in dB, rounded to 0.5 dB
+    ///
+    /// Fails with `Error::InvalidArgument` outside of `MIN_TONE_DB` to `MAX_TONE_DB`
+    pub async fn set_bass(&self, db: f32) -> Result<()> {
+        let level = tone::tone_level(db)?;
+        self.put(&["Sound_Video", "Tone", "Bass"], level.to_value()).await?;
+        Ok(())
+    }
+
+    /// Set the Treble Level in dB, rounded to 0.5 dB
+    ///
+    /// Fails with `Error::InvalidArgument` outside of `MIN_TONE_DB` to `MAX_TONE_DB`
+    pub async fn set_treble(&self, db: f32) -> Result<()> {
+        let level = tone::tone_level(db)?;
+        self.put(&["Sound_Video", "Tone", "Treble"], level.to_value()).await?;
+        Ok(())
+    }
+
+    /// Pure Direct, None for Zones without this Setting
+    pub async fn get_pure_direct(&self) -> Result<Option<bool>> {
+        let info = self.get_basic_info().await?;
+        Ok(info.sound_video.map(|sound_video| sound_video.direct))
+    }
+
+    /// Bypass all Processing except the Volume
+    pub async fn set_pure_direct(&self, value: bool) -> Result<()> {
+        self.put(&["Sound_Video", "Direct", "Mode"], Value::on_off(value)).await?;
+        Ok(())
+    }
+
+    /// Adaptive DRC, None for Zones without this Setting
+    pub async fn get_adaptive_drc(&self) -> Result<Option<bool>> {
+        let info = self.get_basic_info().await?;
+        Ok(info.sound_video.map(|sound_video| sound_video.adaptive_drc))
+    }
+
+    /// Compress the Dynamic Range depending on the Volume, the Receiver reports it as `Auto` when enabled
+    pub async fn set_adaptive_drc(&self, value: bool) -> Result<()> {
+        self.put(&["Sound_Video", "Adaptive_DRC"], if value { "Auto" } else { "Off" }).await?;
+        Ok(())
+    }
+
+    /// The HDMI Outputs of this Zone, empty when the Zone does not report them
+    pub async fn get_hdmi_outputs(&self) -> Result<Vec<HdmiOutput>> {
+        let info = self.get_basic_info().await?;
+        Ok(info.sound_video.map(|sound_video| sound_video.hdmi_outputs).unwrap_or_default())
+    }
+
+    /// Enable or disable a HDMI Output, starting at 1
+    pub async fn set_hdmi_output(&self, output: u8, value: bool) -> Result<()> {
+        if output == 0 {
+            return Err(Error::InvalidArgument(format!("Invalid HDMI output {}", output)));
+        }
+        self.put(&["Sound_Video", "HDMI", "Output", &format!("OUT_{}", output)], Value::on_off(value)).await?;
+        Ok(())
+    }
+
+    pub async fn get_input(&self) -> Result<InputId> {
+        let info = self.get_basic_info().await?;
+        info.input.ok_or_else(|| Error::UnexpectedResponse("Missing Input_Sel".to_owned()))
+    }
+
+    /// Select a Input for this Zone.
+    ///
+    /// Fails with `Error::InvalidValue` when the Input is not available
+    pub async fn select_input(&self, input: &InputId) -> Result<()> {
+        self.put(&["Input", "Input_Sel"], input.command_name()).await?;
+        Ok(())
+    }
+
+    /// Select a Input by its Display Name, e.g. `Turntable`, and return the selected Input
+    ///
+    /// Fails with `Error::AmbiguousInput` when the Name matches more than one Input
+    pub async fn select_input_by_name(&self, name: &str) -> Result<InputId> {
+        let inputs = self.avr.get_inputs().await?;
+        let input = system_config::find_input(&inputs, name)?;
+        self.select_input(&input.id).await?;
+        Ok(input.id.clone())
+    }
+
+    /// Fails with `Error::UnexpectedResponse` when the Zone reports no or a unknown Sleep Timer
+    pub async fn get_sleep(&self) -> Result<SleepTimer> {
+        let info = self.get_basic_info().await?;
+        info.sleep.ok_or_else(|| Error::UnexpectedResponse("Unknown Power_Control/Sleep".to_owned()))
+    }
+
+    pub async fn set_sleep(&self, value: SleepTimer) -> Result<()> {
+        self.put(&["Power_Control", "Sleep"], value.value()).await?;
+        Ok(())
+    }
+
+    /// The Scenes of this Zone with their Titles
+    pub async fn get_scenes(&self) -> Result<Vec<Scene>> {
+        let res = self.get(&["Scene", "Scene_Sel_Item"]).await?;
+        scene::parse_scenes(res)
+    }
+
+    /// Recall one of the Scenes of this Zone, starting at 1
+    pub async fn recall_scene(&self, scene: u8) -> Result<()> {
+        if !(1..=12).contains(&scene) {
+            return Err(Error::InvalidArgument(format!("Invalid scene {}", scene)));
+        }
+        self.put(&["Scene", "Scene_Sel"], format!("Scene {}", scene)).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_should_parse_tags_and_short_names() {
+        assert_eq!("Zone_2".parse::<Zone>().unwrap(), Zone::Zone2);
+        assert_eq!("main".parse::<Zone>().unwrap(), Zone::Main);
+        assert_eq!("zone4".parse::<Zone>().unwrap(), Zone::Zone4);
+        assert!("Zone_5".parse::<Zone>().is_err());
+    }
+}